@@ -2,38 +2,242 @@
 //! from.
 //!
 //! This is useful for when you want to continue using a vector while keeping
-//! references to its contents.
+//! references to its contents. The compiler can't check that those references
+//! aren't used after the vector is dropped or its buffer grows, so the methods
+//! that return them are `unsafe`. See `Detach` for what their callers must
+//! uphold.
 //!
 //! # Example
-//! ```
+#![cfg_attr(feature = "safe-only", doc = "```ignore")]
+#![cfg_attr(not(feature = "safe-only"), doc = "```")]
 //! use push_vec::prelude::*;
-//! let mut vec = push_vec![];
-//! let x: &mut i32 = vec.push(1);
+//! // Reserve room for both pushes, so that the buffer doesn't grow.
+//! let mut vec = PushVec::with_capacity(2);
+//! // SAFETY: `x` isn't used after the buffer grows or `vec` is dropped.
+//! let x: &mut i32 = unsafe { vec.push(1) };
 //! // We are holding a reference to an element, but we can still use the vector.
-//! vec.push(2);
+//! unsafe { vec.push(2) };
 //! *x = 3;
 //! assert_eq!(vec.into_vec(), vec![3, 2]);
 //! ```
+//!
+//! # Features
+//! - `safe-only`: ties the references returned by `PushVec<T>` to the borrow
+//!   of the `PushVec<T>`, like a normal `Vec<T>`, which makes the methods that
+//!   return them safe. See `Detach`. The `unsafe` that isn't about detaching
+//!   stays: the `Pod` trait with `encode` and `decode`, and the `unsafe fn`s
+//!   like `from_raw_parts`.
+//!
+//!   This feature is not additive. It changes the signatures of the public
+//!   methods, and Cargo enables a feature for every crate in the build once
+//!   any crate asks for it. So if one dependency enables `safe-only`, every
+//!   other crate in the build that holds detached references stops
+//!   compiling. Only enable it in a final binary, or for testing.
+//! - `serde`: implements `Serialize` and `Deserialize`.
+//! - `smallvec`: conversions to and from `SmallVec`.
 
 use std::slice::{self, SliceIndex};
-use std::ops::{Index, IndexMut};
-use std::iter::{FromIterator, IntoIterator};
+use std::ops::{AddAssign, Index, IndexMut, Range, RangeBounds};
+use std::iter::{self, FromIterator, IntoIterator, Sum};
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet, TryReserveError};
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+use std::mem::{self, MaybeUninit};
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+#[cfg(feature = "serde")]
+use serde::de::{SeqAccess, Visitor};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "smallvec")]
+use smallvec::SmallVec;
+
+/// Relates the lifetime `'vec` of a borrow of a `PushVec<T>` to the lifetime
+/// `'a` of the references obtained through it.
+///
+/// By default the two are unrelated, so the references are detached from the
+/// `PushVec<T>`, and it can be pushed to while they are held. The compiler
+/// can't check how detached references are used, so the methods that return
+/// them are `unsafe fn`s, whose callers must make sure that a detached
+/// reference isn't used after:
+/// - the `PushVec<T>` is dropped, or consumed by a method that takes it by
+///   value, like `into_vec`.
+/// - the buffer of the `PushVec<T>` grows, or its elements are moved, like by
+///   `insert`. The elements are stored contiguously, so growing moves all of
+///   them. `can_push_without_realloc` tells if a push would grow the buffer,
+///   and reserving up front, with `with_capacity`, `reserve_total` or
+///   `builder`, keeps it from growing.
+/// - the element it points at is accessed in a way that the reference doesn't
+///   allow: a mutable reference must not be used after its element is
+///   accessed in any other way, and a shared one after its element is
+///   mutated. Comparing, indexing, iterating over and slicing the
+///   `PushVec<T>` all access every element, so the mutable references must
+///   not be used after any of them.
+///
+/// With the `safe-only` feature, `'vec` must outlive `'a`, which turns every
+/// method into an ordinary borrow that is checked by the compiler, and an
+/// ordinary safe method. The lifetime casts and the raw pointer accesses that
+/// detaching relies on are then compiled out.
+///
+/// This trait is implemented for every type, and can't be implemented or used
+/// outside of this crate.
+pub trait Detach<'vec, 'a>: 'a {
+    #[doc(hidden)]
+    fn detach<U: ?Sized>(r: &'vec U, token: Token) -> &'a U;
+
+    #[doc(hidden)]
+    fn detach_mut<U: ?Sized>(r: &'vec mut U, token: Token) -> &'a mut U;
+}
+
+#[cfg(not(feature = "safe-only"))]
+impl<'vec, 'a, S: ?Sized + 'a> Detach<'vec, 'a> for S {
+    #[inline]
+    fn detach<U: ?Sized>(r: &'vec U, _: Token) -> &'a U {
+        unsafe { &*(r as *const U) }
+    }
+
+    #[inline]
+    fn detach_mut<U: ?Sized>(r: &'vec mut U, _: Token) -> &'a mut U {
+        unsafe { &mut *(r as *mut U) }
+    }
+}
+
+#[cfg(feature = "safe-only")]
+impl<'vec: 'a, 'a, S: ?Sized + 'a> Detach<'vec, 'a> for S {
+    #[inline]
+    fn detach<U: ?Sized>(r: &'vec U, _: Token) -> &'a U {
+        r
+    }
+
+    #[inline]
+    fn detach_mut<U: ?Sized>(r: &'vec mut U, _: Token) -> &'a mut U {
+        r
+    }
+}
+
+mod token {
+    /// Only this crate can create a `Token`, so only it can call `Detach`.
+    #[derive(Clone, Copy, Debug)]
+    pub struct Token(pub(crate) ());
+}
+
+use token::Token;
+
+/// Declares a method that returns detached references: an `unsafe fn` whose
+/// safety contract is that of `Detach`, or with `safe-only`, a safe one.
+macro_rules! detached {
+    ($(#[$attr:meta])* pub fn $($rest:tt)*) => {
+        $(#[$attr])*
+        ///
+        /// # Safety
+        /// The returned references are detached, so they must not be used
+        /// after the collection they point into is dropped or grows, or in a
+        /// way that breaks the aliasing rules. See `Detach` for the details.
+        /// With the `safe-only` feature, this method is safe.
+        #[cfg(not(feature = "safe-only"))]
+        pub unsafe fn $($rest)*
+
+        $(#[$attr])*
+        #[cfg(feature = "safe-only")]
+        pub fn $($rest)*
+    };
+}
 
 /// A vector-like type that supports only push operations.
 ///
 /// # Example
-/// ```
+#[cfg_attr(feature = "safe-only", doc = "```ignore")]
+#[cfg_attr(not(feature = "safe-only"), doc = "```")]
 /// use push_vec::prelude::*;
-/// let mut vec = push_vec![];
-/// let x: &mut i32 = vec.push(1);
+/// // Reserve room for both pushes, so that the buffer doesn't grow.
+/// let mut vec = PushVec::with_capacity(2);
+/// // SAFETY: `x` isn't used after the buffer grows or `vec` is dropped.
+/// let x: &mut i32 = unsafe { vec.push(1) };
 /// // We are holding a reference to an element, but we can still use the vector.
-/// vec.push(2);
+/// unsafe { vec.push(2) };
 /// *x = 3;
 /// assert_eq!(vec.into_vec(), vec![3, 2]);
 /// ```
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct PushVec<T>(Vec<T>);
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct PushVec<T>(Vec<T>, Growth);
+
+/// Hooks into the growth of the buffer of a `PushVec<T>`.
+///
+/// It takes no part in comparisons and hashing, and its callback isn't
+/// cloned, so the `PushVec<T>` derives behave as if it held only its elements.
+/// The hooks are boxed, so that they cost a `PushVec<T>` a single pointer.
+#[derive(Default)]
+struct Growth(Option<Box<Hooks>>);
+
+#[derive(Default)]
+struct Hooks {
+    on_realloc: Option<OnRealloc>,
+    factor: Option<f64>,
+}
+
+/// An `on_realloc` callback.
+struct OnRealloc(Box<dyn FnMut(usize, usize) + Send>);
+
+// SAFETY: The callback is only called through `&mut`, so a shared reference
+// to it can't be used to call it from several threads at once.
+unsafe impl Sync for OnRealloc {}
+
+impl Growth {
+    const fn new() -> Self {
+        Growth(None)
+    }
+
+    fn hooks(&mut self) -> &mut Hooks {
+        self.0.get_or_insert_with(Default::default)
+    }
+
+    fn factor(&self) -> Option<f64> {
+        self.0.as_ref()?.factor
+    }
+
+    fn on_realloc(&mut self) -> Option<&mut (dyn FnMut(usize, usize) + Send)> {
+        Some(&mut *self.0.as_mut()?.on_realloc.as_mut()?.0)
+    }
+}
+
+impl Clone for Growth {
+    fn clone(&self) -> Self {
+        let hooks = |factor| Box::new(Hooks { on_realloc: None, factor: Some(factor) });
+        Growth(self.factor().map(hooks))
+    }
+}
+
+impl PartialEq for Growth {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Growth {}
+
+impl PartialOrd for Growth {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Growth {
+    fn cmp(&self, _: &Self) -> Ordering {
+        Ordering::Equal
+    }
+}
+
+impl Hash for Growth {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+// A callback that panics can't break the invariants of the `PushVec<T>`, as
+// it is only called after the buffer has grown.
+impl UnwindSafe for Growth {}
+impl RefUnwindSafe for Growth {}
 
 impl<T> PushVec<T> {
     /// Creates a new, empty `PushVec<T>`.
@@ -45,7 +249,55 @@ impl<T> PushVec<T> {
     /// ```
     #[inline]
     pub const fn new() -> Self {
-        PushVec(Vec::new())
+        PushVec(Vec::new(), Growth::new())
+    }
+
+    /// Creates a new, empty `PushVec<T>` with at least the specified capacity.
+    /// This is the same as `Vec<T>::with_capacity()`.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec: PushVec<i32> = PushVec::with_capacity(10);
+    /// assert!(vec.capacity() >= 10);
+    /// ```
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        PushVec::from_vec(Vec::with_capacity(capacity))
+    }
+
+    /// Creates a `PushVec<T>` of `n` clones of `value`.
+    /// This is the same as `push_vec![value; n]`.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec = PushVec::repeat(0, 4);
+    /// assert_eq!(vec, push_vec![0, 0, 0, 0]);
+    /// ```
+    #[inline]
+    pub fn repeat(value: T, n: usize) -> Self
+        where T: Clone,
+    {
+        PushVec::from_vec(vec![value; n])
+    }
+
+    /// Creates a `PushVecBuilder<T>` that can push up to `capacity` elements
+    /// without ever reallocating.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+    #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+    /// use push_vec::prelude::*;
+    /// let mut builder = PushVec::builder(2);
+    /// let x = unsafe { builder.push(1) };
+    /// unsafe { builder.push(2) };
+    /// *x = 3;
+    /// assert_eq!(builder.build(), push_vec![3, 2]);
+    /// ```
+    #[inline]
+    pub fn builder(capacity: usize) -> PushVecBuilder<T> {
+        PushVecBuilder::new(capacity)
     }
 
     /// Creates a `PushVec<T>` from a `Vec<T>`.
@@ -58,271 +310,4250 @@ impl<T> PushVec<T> {
     /// ```
     #[inline]
     pub const fn from_vec(vec: Vec<T>) -> Self {
-        PushVec(vec)
+        PushVec(vec, Growth::new())
     }
 
-    /// Cheaply converts a `PushVec<T>` into a `Vec<T>`.
+    /// Creates a `PushVec<T>` directly from a pointer, a length and a
+    /// capacity, as `Vec::from_raw_parts` does, for buffers that were handed
+    /// over through FFI.
+    ///
+    /// # Safety
+    /// The safety contract of `Vec::from_raw_parts` must be upheld:
+    /// - `ptr` must have been allocated by the global allocator, with the
+    ///   alignment of `T`, unless `cap` is 0 or `T` is zero-sized.
+    /// - The allocation must be exactly `cap * size_of::<T>()` bytes.
+    /// - `len` must not exceed `cap`, and the first `len` elements must be
+    ///   initialized values of `T`.
+    /// - Nothing else may own or use the buffer afterwards, since the
+    ///   `PushVec<T>` frees it when dropped.
     ///
     /// # Example
     /// ```
     /// use push_vec::prelude::*;
-    /// let vec1 = push_vec![1, 2, 3];
-    /// let vec2 = vec![1, 2, 3];
-    /// assert_eq!(vec1.into_vec(), vec2);
+    /// use std::mem::ManuallyDrop;
+    /// let mut vec = ManuallyDrop::new(vec![1, 2, 3]);
+    /// let (ptr, len, cap) = (vec.as_mut_ptr(), vec.len(), vec.capacity());
+    /// // The parts come from a `Vec<i32>` that is never used again.
+    /// let vec = unsafe { PushVec::from_raw_parts(ptr, len, cap) };
+    /// assert_eq!(vec.capacity(), cap);
+    /// assert_eq!(vec.into_vec(), [1, 2, 3]);
     /// ```
     #[inline]
-    pub fn into_vec(self) -> Vec<T> {
-        self.0
+    pub unsafe fn from_raw_parts(ptr: *mut T, len: usize, cap: usize) -> Self {
+        PushVec::from_vec(Vec::from_raw_parts(ptr, len, cap))
     }
 
-    /// Returns an immutable reference to the underlying `Vec<T>`.
+    /// Collects an iterator of `Result`s into a `PushVec<T>`, stopping at the
+    /// first error.
+    ///
+    /// # Errors
+    /// Returns the first error the iterator yields.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let ok: Result<PushVec<i32>, &str> = PushVec::try_from_iter([Ok(1), Ok(2)]);
+    /// assert_eq!(ok, Ok(push_vec![1, 2]));
+    /// let err = PushVec::try_from_iter([Ok(1), Err("bad"), Ok(3)]);
+    /// assert_eq!(err, Err("bad"));
+    /// ```
+    #[inline]
+    pub fn try_from_iter<E, I>(iter: I) -> Result<PushVec<T>, E>
+        where I: IntoIterator<Item = Result<T, E>>,
+    {
+        iter.into_iter().collect::<Result<Vec<T>, E>>().map(PushVec::from_vec)
+    }
+
+    /// Cheaply converts a `PushVec<T>` into a `Vec<T>`.
+    ///
+    /// The references previously returned by the `PushVec<T>` must not be used
+    /// after this. The compiler only rejects it with the `safe-only` feature,
+    /// since otherwise the references aren't tied to the `PushVec<T>`.
     ///
     /// # Example
     /// ```
     /// use push_vec::prelude::*;
     /// let vec1 = push_vec![1, 2, 3];
     /// let vec2 = vec![1, 2, 3];
-    /// assert_eq!(vec1.as_vec(), &vec2);
+    /// assert_eq!(vec1.into_vec(), vec2);
     /// ```
     #[inline]
-    pub const fn as_vec(&self) -> &Vec<T> {
-        &self.0
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
     }
 
-    /// Returns the length of the `PushVec<T>`.
-    /// This is the same as `Vec<T>::len()`.
-    /// 
+    /// Converts a `PushVec<T>` into a `Vec<T>` without spare capacity.
+    /// Unlike `into_vec`, this may reallocate to shrink the buffer.
+    ///
     /// # Example
     /// ```
     /// use push_vec::prelude::*;
-    /// let vec = push_vec![1, 2, 3];
-    /// assert_eq!(vec.len(), 3);
+    /// let mut vec = PushVec::with_capacity(10);
+    /// vec.extend([1, 2, 3]);
+    /// let vec = vec.into_vec_exact();
+    /// assert_eq!(vec.capacity(), vec.len());
     /// ```
     #[inline]
-    pub fn len(&self) -> usize {
-        self.0.len()
+    pub fn into_vec_exact(self) -> Vec<T> {
+        let mut vec = self.0;
+        vec.shrink_to_fit();
+        vec
     }
 
-    /// Returns `true` if the `PushVec<T>` is empty.
-    /// This is the same as `Vec<T>::is_empty()`.
+    /// Converts a `PushVec<T>` into a `Vec<T>` with a capacity of at least
+    /// `capacity`, reserving if the buffer is smaller, for pushing to the
+    /// `Vec<T>` afterwards.
+    ///
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
     ///
     /// # Example
     /// ```
     /// use push_vec::prelude::*;
-    /// let mut vec = push_vec![];
-    /// assert!(vec.is_empty());
-    /// vec.push(1);
-    /// assert!(!vec.is_empty());
+    /// let vec = push_vec![1, 2, 3].into_vec_with_capacity(10);
+    /// assert!(vec.capacity() >= 10);
+    /// assert_eq!(vec, [1, 2, 3]);
     /// ```
     #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+    pub fn into_vec_with_capacity(self, capacity: usize) -> Vec<T> {
+        let mut vec = self.0;
+        vec.reserve(capacity.saturating_sub(vec.len()));
+        vec
     }
 
-    /// Returns a mutable reference to the elements, and doesn't borrow the
-    /// `PushVec<T>`.
+    /// Converts a `PushVec<T>` into a boxed slice without spare capacity,
+    /// freezing it into the least memory that holds its elements.
+    ///
+    /// Shrinking the buffer may move the elements, but the conversion
+    /// consumes the `PushVec<T>`, so no references to them can be held across
+    /// it. Use `seal` instead to keep the `PushVec<T>` detached-reference API.
     ///
     /// # Example
     /// ```
     /// use push_vec::prelude::*;
-    /// let mut vec = push_vec![1, 2, 3];
-    /// let slice = vec.as_mut_slice();
-    /// // We can still push some elements
-    /// vec.push(4);
-    /// // And also use the slice
-    /// slice[0] = 5;
+    /// let mut vec = PushVec::with_capacity(10);
+    /// vec.extend([1, 2, 3]);
+    /// let boxed = vec.into_exact_boxed();
+    /// assert_eq!(&*boxed, &[1, 2, 3]);
+    /// assert_eq!(boxed.into_vec().capacity(), 3);
     /// ```
     #[inline]
-    pub fn as_mut_slice<'vec, 'a>(&'vec mut self) -> &'a mut [T]
-        where Self: 'a,
-    {
-        unsafe { slice::from_raw_parts_mut(self.0.as_mut_ptr(), self.0.len()) }
+    pub fn into_exact_boxed(self) -> Box<[T]> {
+        self.0.into_boxed_slice()
     }
 
-    /// Returns a reference to the elements, and doesn't borrow the `PushVec<T>`.
+    /// Returns an immutable reference to the underlying `Vec<T>`.
     ///
     /// # Example
     /// ```
     /// use push_vec::prelude::*;
-    /// let mut vec = push_vec![1, 2, 3];
-    /// let slice = vec.as_slice();
-    /// // We can still push some elements
-    /// vec.push(4);
-    /// // And also use the slice
-    /// println!("{}", slice[0]);
+    /// let vec1 = push_vec![1, 2, 3];
+    /// let vec2 = vec![1, 2, 3];
+    /// assert_eq!(vec1.as_vec(), &vec2);
     /// ```
     #[inline]
-    pub fn as_slice<'vec, 'a>(&'vec self) -> &'a [T]
-        where Self: 'a,
-    {
-        unsafe { slice::from_raw_parts(self.0.as_ptr(), self.0.len()) }
+    pub const fn as_vec(&self) -> &Vec<T> {
+        &self.0
     }
 
-    /// Returns a mutable reference to the element at the given index, and
-    /// doesn't borrow the `PushVec<T>`.
+    /// Returns a mutable reference to the underlying `Vec<T>`.
+    ///
+    /// This gives up the push-only semantics: anything that reallocates or
+    /// removes elements, like `reserve`, `truncate` or `clear`, invalidates
+    /// the references previously returned by the `PushVec<T>`. Make sure none
+    /// are held, or that they don't point at elements the operation moves.
+    ///
+    /// Growth through the `Vec<T>` isn't reported to the `on_realloc`
+    /// callback.
     ///
     /// # Example
     /// ```
     /// use push_vec::prelude::*;
     /// let mut vec = push_vec![1, 2, 3];
-    /// let x: &mut i32 = vec.get_mut(1).unwrap();
-    /// *x = 4;
-    /// assert_eq!(vec.into_vec(), vec![1, 4, 3]);
+    /// vec.as_vec_mut().reserve(10);
+    /// let capacity = vec.capacity();
+    /// vec.extend(4..14);
+    /// assert_eq!(vec.capacity(), capacity);
     /// ```
     #[inline]
-    pub fn get_mut<'vec, 'a>(&'vec mut self, index: usize) -> Option<&'a mut T>
-        where Self: 'a,
-    {
-        self.as_mut_slice().get_mut(index)
+    pub fn as_vec_mut(&mut self) -> &mut Vec<T> {
+        &mut self.0
     }
 
-    /// Returns a reference to the element at the given index, and doesn't
-    /// borrow the `PushVec<T>`.
+    /// Returns the length of the `PushVec<T>`.
+    /// This is the same as `Vec<T>::len()`.
+    /// 
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec = push_vec![1, 2, 3];
+    /// assert_eq!(vec.len(), 3);
+    /// ```
     #[inline]
-    pub fn get<'vec, 'a>(&'vec self, index: usize) -> Option<&'a T>
-        where Self: 'a,
-    {
-        self.as_slice().get(index)
+    pub fn len(&self) -> usize {
+        self.0.len()
     }
 
-    /// Returns an iterator over the elements of the `PushVec<T>`.
-    /// Doesn't borrow the `PushVec<T>`.
+    /// Returns the number of elements the `PushVec<T>` can hold without
+    /// reallocating.
+    /// This is the same as `Vec<T>::capacity()`.
     ///
     /// # Example
     /// ```
     /// use push_vec::prelude::*;
-    /// let mut vec = push_vec![1, 2, 3];
-    /// for x in vec.iter() {
-    ///    println!("{}", x);
-    ///    vec.push(4);
-    /// }
+    /// let vec: PushVec<i32> = PushVec::with_capacity(10);
+    /// assert!(vec.capacity() >= 10);
     /// ```
     #[inline]
-    pub fn iter<'vec, 'a>(&'vec self) -> impl Iterator<Item = &'a T>
-        where Self: 'a,
-    {
-        self.as_slice().iter()
+    pub fn capacity(&self) -> usize {
+        self.0.capacity()
     }
 
-    /// Returns a mutable iterator over the elements of the `PushVec<T>`.
-    /// Doesn't borrow the `PushVec<T>`.
+    /// Returns the number of elements that can be pushed before the
+    /// `PushVec<T>` reallocates, and references to its elements are
+    /// invalidated.
     ///
     /// # Example
     /// ```
     /// use push_vec::prelude::*;
-    /// let mut vec = push_vec![1, 2, 3];
-    /// for x in vec.iter_mut() {
-    ///   *x += 1;
-    ///   vec.push(5);
-    /// }
+    /// let mut vec = PushVec::new();
+    /// vec.as_vec_mut().reserve_exact(10);
+    /// vec.extend([1, 2]);
+    /// assert_eq!(vec.spare_capacity(), 8);
     /// ```
     #[inline]
-    pub fn iter_mut<'vec, 'a>(&mut self) -> impl Iterator<Item = &'a mut T>
-        where Self: 'a,
-    {
-        self.as_mut_slice().iter_mut()
+    pub fn spare_capacity(&self) -> usize {
+        self.0.capacity() - self.0.len()
     }
 
+    /// Returns `true` if `additional` more elements can be pushed without
+    /// reallocating, so that references to the elements stay valid.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = PushVec::new();
+    /// vec.as_vec_mut().reserve_exact(5);
+    /// vec.extend([1, 2]);
+    /// assert!(vec.can_push_without_realloc(3));
+    /// assert!(!vec.can_push_without_realloc(4));
+    /// ```
     #[inline]
-    pub fn into_iter(self) -> impl Iterator<Item = T> {
-        self.0.into_iter()
+    pub fn can_push_without_realloc(&self, additional: usize) -> bool {
+        additional <= self.spare_capacity()
     }
 
-    /// Pushes an element to the back of the `PushVec<T>`.
-    /// Returns a mutable reference to the pushed element.
-    /// Doesn't borrow the `PushVec<T>`.
+    /// Returns `true` if the `PushVec<T>` is empty.
+    /// This is the same as `Vec<T>::is_empty()`.
     ///
     /// # Example
     /// ```
     /// use push_vec::prelude::*;
-    /// let mut vec = push_vec![1, 2, 3];
-    /// let x = vec.push(4);
-    /// let y = vec.push(5);
-    /// assert_eq!(vec, push_vec![1, 2, 3, 4, 5]);
-    /// *x = 6;
-    /// *y = 7;
-    /// assert_eq!(vec, push_vec![1, 2, 3, 6, 7]);
+    /// let mut vec = push_vec![];
+    /// assert!(vec.is_empty());
+    /// vec.extend([1]);
+    /// assert!(!vec.is_empty());
     /// ```
     #[inline]
-    pub fn push<'vec, 'a>(&'vec mut self, item: T) -> &'a mut T 
-        where Self: 'a,
-    {
-        self.0.push(item);
-        // This is safe because elements are never dropped before the vector is
-        // and the reference surely exists.
-        unsafe {
-            let ptr = self.0.as_mut_ptr().offset(self.0.len() as isize - 1);
-            &mut *ptr
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    detached! {
+        /// Returns a mutable reference to the elements, and doesn't borrow the
+        /// `PushVec<T>`.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// // Reserve room for the push, so that the slice stays valid.
+        /// let mut vec = PushVec::with_capacity(4);
+        /// vec.extend([1, 2, 3]);
+        /// let slice = unsafe { vec.as_mut_slice() };
+        /// // We can still push some elements
+        /// unsafe { vec.push(4) };
+        /// // And also use the slice
+        /// slice[0] = 5;
+        /// ```
+        #[inline]
+        pub fn as_mut_slice<'vec, 'a>(&'vec mut self) -> &'a mut [T]
+            where Self: Detach<'vec, 'a>,
+        {
+            Self::detach_mut(self.0.as_mut_slice(), Token(()))
         }
     }
-}
 
-impl<T> AsRef<[T]> for PushVec<T> {
-    fn as_ref(&self) -> &[T] {
-        &self.0
+    detached! {
+        /// Returns a reference to the elements, and doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// // Reserve room for the push, so that the slice stays valid.
+        /// let mut vec = PushVec::with_capacity(4);
+        /// vec.extend([1, 2, 3]);
+        /// let slice = unsafe { vec.as_slice() };
+        /// // We can still push some elements
+        /// unsafe { vec.push(4) };
+        /// // And also use the slice
+        /// println!("{}", slice[0]);
+        /// ```
+        #[inline]
+        pub fn as_slice<'vec, 'a>(&'vec self) -> &'a [T]
+            where Self: Detach<'vec, 'a>,
+        {
+            Self::detach(self.0.as_slice(), Token(()))
+        }
     }
-}
 
-impl<T> AsMut<[T]> for PushVec<T> {
-    fn as_mut(&mut self) -> &mut [T] {
-        &mut self.0
+    detached! {
+        /// Returns a mutable reference to the element at the given index, and
+        /// doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let mut vec = push_vec![1, 2, 3];
+        /// let x: &mut i32 = unsafe { vec.get_mut(1) }.unwrap();
+        /// *x = 4;
+        /// assert_eq!(vec.into_vec(), vec![1, 4, 3]);
+        /// ```
+        #[inline]
+        pub fn get_mut<'vec, 'a>(&'vec mut self, index: usize) -> Option<&'a mut T>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_mut_slice().get_mut(index)
+        }
     }
-}
 
-impl<T> From<Vec<T>> for PushVec<T> {
-    fn from(vec: Vec<T>) -> Self {
-        PushVec::from_vec(vec)
+    detached! {
+        /// Returns a mutable reference to the element at the given index, whose
+        /// lifetime is detached from the `PushVec<T>`.
+        ///
+        /// This is the same as `get_mut`, but names the intent explicitly: the
+        /// returned reference lives on while the `PushVec<T>` is pushed to, unlike
+        /// a reference obtained through `IndexMut` or `AsMut`, which borrows it.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(3);
+        /// vec.extend([1, 2]);
+        /// let x = unsafe { vec.leak_element(0) }.unwrap();
+        /// unsafe { vec.push(3) };
+        /// *x = 4;
+        /// assert_eq!(vec, push_vec![4, 2, 3]);
+        /// assert!(unsafe { vec.leak_element(3) }.is_none());
+        /// ```
+        ///
+        /// Whereas indexing borrows the `PushVec<T>`:
+        /// ```compile_fail
+        /// use push_vec::prelude::*;
+        /// let mut vec = push_vec![1, 2];
+        /// let x = &mut vec[0];
+        /// vec.push(3);
+        /// *x = 4;
+        /// ```
+        #[inline]
+        pub fn leak_element<'vec, 'a>(&'vec mut self, index: usize) -> Option<&'a mut T>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.get_mut(index)
+        }
     }
-}
 
-impl<T> From<PushVec<T>> for Vec<T> {
-    fn from(push_vec: PushVec<T>) -> Self {
-        push_vec.into_vec()
+    detached! {
+        /// Returns a reference to the element at the given index, and doesn't
+        /// borrow the `PushVec<T>`.
+        #[inline]
+        pub fn get<'vec, 'a>(&'vec self, index: usize) -> Option<&'a T>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_slice().get(index)
+        }
     }
-}
 
-impl<T> FromIterator<T> for PushVec<T> {
-    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        PushVec::from_vec(Vec::from_iter(iter))
+    /// Returns a copy of the element at the given index, or `None` if it's out
+    /// of bounds.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec = push_vec![1, 2, 3];
+    /// assert_eq!(vec.get_copied(1), Some(2));
+    /// assert_eq!(vec.get_copied(3), None);
+    /// ```
+    #[inline]
+    pub fn get_copied(&self, index: usize) -> Option<T>
+        where T: Copy,
+    {
+        self.0.get(index).copied()
     }
-}
 
-impl<T> Extend<T> for PushVec<T> {
-    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        self.0.extend(iter);
+    detached! {
+        /// Returns mutable references to the two elements at the given indices,
+        /// and doesn't borrow the `PushVec<T>`.
+        /// Returns `None` if the indices are equal or either is out of bounds.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(4);
+        /// vec.extend([1, 2, 3]);
+        /// let (x, y) = unsafe { vec.get_two_mut(0, 2) }.unwrap();
+        /// unsafe { vec.push(4) };
+        /// std::mem::swap(x, y);
+        /// assert_eq!(vec, push_vec![3, 2, 1, 4]);
+        /// assert!(unsafe { vec.get_two_mut(1, 1) }.is_none());
+        /// assert!(unsafe { vec.get_two_mut(1, 4) }.is_none());
+        /// ```
+        #[inline]
+        pub fn get_two_mut<'vec, 'a>(&'vec mut self, i: usize, j: usize) -> Option<(&'a mut T, &'a mut T)>
+            where Self: Detach<'vec, 'a>,
+        {
+            if i == j || i >= self.len() || j >= self.len() {
+                return None;
+            }
+            let slice = self.as_mut_slice();
+            let (first, second) = slice.split_at_mut(i.max(j));
+            let (low, high) = (&mut first[i.min(j)], &mut second[0]);
+            if i < j {
+                Some((low, high))
+            } else {
+                Some((high, low))
+            }
+        }
     }
-}
 
-impl<T, I> Index<I> for PushVec<T>
-    where I: SliceIndex<[T]>
-{
-    type Output = I::Output;
+    detached! {
+        /// Returns an iterator over the elements of the `PushVec<T>`.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// // Reserve room for the pushes, so that the iterator stays valid.
+        /// let mut vec = PushVec::with_capacity(6);
+        /// vec.extend([1, 2, 3]);
+        /// for x in unsafe { vec.iter() } {
+        ///    println!("{}", x);
+        ///    unsafe { vec.push(4) };
+        /// }
+        /// ```
+        #[inline]
+        pub fn iter<'vec, 'a>(&'vec self) -> impl Iterator<Item = &'a T>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_slice().iter()
+        }
+    }
 
-    fn index(&self, index: I) -> &I::Output {
-        &self.0[index]
+    detached! {
+        /// Returns a mutable iterator over the elements of the `PushVec<T>`.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// // Reserve room for the pushes, so that the iterator stays valid.
+        /// let mut vec = PushVec::with_capacity(6);
+        /// vec.extend([1, 2, 3]);
+        /// for x in unsafe { vec.iter_mut() } {
+        ///   *x += 1;
+        ///   unsafe { vec.push(5) };
+        /// }
+        /// ```
+        #[inline]
+        pub fn iter_mut<'vec, 'a>(&'vec mut self) -> impl Iterator<Item = &'a mut T>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_mut_slice().iter_mut()
+        }
     }
-}
 
-impl<T, I> IndexMut<I> for PushVec<T>
-    where I: SliceIndex<[T]>
-{
-    fn index_mut(&mut self, index: I) -> &mut I::Output {
-        &mut self.0[index]
+    detached! {
+        /// Returns an iterator over the elements of the `PushVec<T>` and their
+        /// indices.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(6);
+        /// vec.extend([1, 2, 3]);
+        /// for (i, x) in unsafe { vec.iter_indexed() } {
+        ///     unsafe { vec.push(i * 10 + x) };
+        /// }
+        /// assert_eq!(vec, push_vec![1, 2, 3, 1, 12, 23]);
+        /// ```
+        #[inline]
+        pub fn iter_indexed<'vec, 'a>(&'vec self) -> impl Iterator<Item = (usize, &'a T)>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_slice().iter().enumerate()
+        }
+    }
+
+    detached! {
+        /// Returns a mutable iterator over the elements of the `PushVec<T>` and
+        /// their indices.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(6);
+        /// vec.extend([7, 7, 7]);
+        /// for (i, x) in unsafe { vec.iter_mut_indexed() } {
+        ///     *x = i;
+        ///     unsafe { vec.push(i * 10) };
+        /// }
+        /// assert_eq!(vec, push_vec![0, 1, 2, 0, 10, 20]);
+        /// ```
+        #[inline]
+        pub fn iter_mut_indexed<'vec, 'a>(&'vec mut self) -> impl Iterator<Item = (usize, &'a mut T)>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_mut_slice().iter_mut().enumerate()
+        }
     }
-}
 
+    detached! {
+        /// Returns an iterator over the elements of the `PushVec<T>` in reverse,
+        /// starting from the most recently pushed one.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(6);
+        /// vec.extend([1, 2, 3]);
+        /// for x in unsafe { vec.iter_rev() } {
+        ///     unsafe { vec.push(*x) };
+        /// }
+        /// assert_eq!(vec, push_vec![1, 2, 3, 3, 2, 1]);
+        /// ```
+        #[inline]
+        pub fn iter_rev<'vec, 'a>(&'vec self) -> impl DoubleEndedIterator<Item = &'a T>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_slice().iter().rev()
+        }
+    }
 
-/// A macro for creating a `PushVec` from a list of elements.
-#[macro_export]
-macro_rules! push_vec {
-    [$($x:expr),*] => {
+    detached! {
+        /// Returns an iterator over `size` elements of the `PushVec<T>` at a time,
+        /// starting at the beginning. The chunks don't overlap, and the last chunk
+        /// may be shorter.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Panics
+        /// Panics if `size` is 0.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(6);
+        /// vec.extend([1, 2, 3, 4, 5]);
+        /// let chunks: Vec<&[i32]> = unsafe { vec.chunks(2) }.collect();
+        /// unsafe { vec.push(6) };
+        /// assert_eq!(chunks, [&[1, 2][..], &[3, 4], &[5]]);
+        /// ```
+        #[inline]
+        pub fn chunks<'vec, 'a>(&'vec self, size: usize) -> impl Iterator<Item = &'a [T]>
+            where Self: Detach<'vec, 'a>,
         {
-            PushVec::from_vec(vec![$($x),*])
+            self.as_slice().chunks(size)
         }
-    };
-    [$x:expr; $n:expr] => {
+    }
+
+    detached! {
+        /// Returns an iterator over `size` elements of the `PushVec<T>` at a time,
+        /// starting at the beginning. The chunks don't overlap, and the last chunk
+        /// may be shorter.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// There is no `windows_mut`, because mutable windows would overlap and
+        /// alias each other. Use disjoint chunks instead, or iterate over the
+        /// indices to mutate overlapping windows.
+        ///
+        /// # Panics
+        /// Panics if `size` is 0.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(6);
+        /// vec.extend([1, 2, 3, 4, 5]);
+        /// let chunks: Vec<&mut [i32]> = unsafe { vec.chunks_mut(2) }.collect();
+        /// // We can still push some elements
+        /// unsafe { vec.push(6) };
+        /// // And also hold all the chunks at once
+        /// for chunk in chunks {
+        ///     chunk.reverse();
+        /// }
+        /// assert_eq!(vec, push_vec![2, 1, 4, 3, 5, 6]);
+        /// ```
+        #[inline]
+        pub fn chunks_mut<'vec, 'a>(&'vec mut self, size: usize) -> impl Iterator<Item = &'a mut [T]>
+            where Self: Detach<'vec, 'a>,
         {
-            PushVec::from_vec(vec![$x; $n])
+            self.as_mut_slice().chunks_mut(size)
+        }
+    }
+
+    detached! {
+        /// Returns an iterator over `n` elements of the `PushVec<T>` at a time,
+        /// starting at the end. The chunks don't overlap, and the leading elements
+        /// that don't fill a chunk are left out - they are returned by the
+        /// iterator's `remainder` method.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Panics
+        /// Panics if `n` is 0.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(8);
+        /// vec.extend(1..8);
+        /// let chunks = unsafe { vec.rchunks_exact(2) };
+        /// unsafe { vec.push(8) };
+        /// assert_eq!(chunks.remainder(), &[1]);
+        /// assert_eq!(chunks.collect::<Vec<_>>(), [&[6, 7][..], &[4, 5], &[2, 3]]);
+        /// ```
+        #[inline]
+        pub fn rchunks_exact<'vec, 'a>(&'vec self, n: usize) -> slice::RChunksExact<'a, T>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_slice().rchunks_exact(n)
+        }
+    }
+
+    detached! {
+        /// Pushes an element to the back of the `PushVec<T>`.
+        /// Returns a mutable reference to the pushed element.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// The references returned by successive pushes never overlap, so they
+        /// can all be held and mutated at once. The compiler doesn't check how
+        /// they are used, which is why this is `unsafe`: they must not be used
+        /// after the `PushVec<T>` is dropped or consumed, as by `into_vec`, nor
+        /// after its buffer grows, since the elements are stored contiguously,
+        /// so growing moves them. Reserve the capacity up front, with
+        /// `with_capacity`, `reserve_total` or `builder`, to keep references
+        /// across any number of pushes.
+        ///
+        /// Reading all the elements at once, such as by comparing or iterating
+        /// over the `PushVec<T>`, reborrows the pushed elements too. The mutable
+        /// references returned earlier must not be used after that.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(5);
+        /// vec.extend([1, 2, 3]);
+        /// // SAFETY: There is room for both pushes, and `x` and `y` aren't used
+        /// // after `vec` is read as a whole.
+        /// let x = unsafe { vec.push(4) };
+        /// let y = unsafe { vec.push(5) };
+        /// *x = 6;
+        /// *y = 7;
+        /// assert_eq!(vec, push_vec![1, 2, 3, 6, 7]);
+        /// ```
+        ///
+        /// # Panics
+        /// Panics if the new capacity exceeds `isize::MAX` bytes.
+        #[inline]
+        pub fn push<'vec, 'a>(&'vec mut self, item: T) -> &'a mut T 
+            where Self: Detach<'vec, 'a>,
+        {
+            let item = self.push_mut(item);
+            Self::detach_mut(item, Token(()))
+        }
+    }
+
+    /// Like `push`, but borrows the `PushVec<T>`, so that it is safe.
+    #[inline]
+    fn push_mut(&mut self, item: T) -> &mut T {
+        self.grow_one(|vec| vec.push(item));
+        let index = self.0.len() - 1;
+        self.element_mut(index)
+    }
+
+    /// Returns the elements in the range, reborrowing only them. Indexing
+    /// would reborrow the whole slice first, which invalidates the references
+    /// to the other elements that were previously returned.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds.
+    #[inline]
+    fn range_mut(&mut self, range: Range<usize>) -> &mut [T] {
+        assert!(range.start <= range.end && range.end <= self.0.len());
+        // SAFETY: The range was just checked to be in bounds. The buffer never
+        // exceeds `isize::MAX` bytes, so the offset fits. For zero-sized types
+        // the length itself may exceed `isize::MAX`, so it must not be cast to
+        // `isize` - `add` takes the unsigned index directly.
+        #[cfg(not(feature = "safe-only"))]
+        let elements = unsafe {
+            slice::from_raw_parts_mut(self.0.as_mut_ptr().add(range.start), range.len())
+        };
+        #[cfg(feature = "safe-only")]
+        let elements = &mut self.0[range];
+        elements
+    }
+
+    /// Returns the element at the index, reborrowing only it. See `range_mut`.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    #[inline]
+    fn element_mut(&mut self, index: usize) -> &mut T {
+        &mut self.range_mut(index..index + 1)[0]
+    }
+
+    detached! {
+        /// Pushes an element to the back of the `PushVec<T>`.
+        /// Returns the index of the pushed element, a mutable reference to it, and
+        /// a slice of all the elements before it.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// The reference and the slice never overlap, so both can be used at once.
+        /// The slice does cover every earlier element, though, so the mutable
+        /// references returned by earlier pushes alias it, and must not be used
+        /// once it has been created.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(4);
+        /// vec.extend([1, 2, 3]);
+        /// let (index, x, before) = unsafe { vec.push_and_context(0) };
+        /// *x = before.iter().sum();
+        /// assert_eq!(index, 3);
+        /// assert_eq!(before, &[1, 2, 3]);
+        /// assert_eq!(vec, push_vec![1, 2, 3, 6]);
+        /// ```
+        #[inline]
+        pub fn push_and_context<'vec, 'a>(&'vec mut self, item: T) -> (usize, &'a mut T, &'a [T])
+            where Self: Detach<'vec, 'a>,
+        {
+            let index = self.len();
+            self.grow_one(|vec| vec.push(item));
+            // As in `range_mut`, the pointer is used rather than reborrowing the
+            // whole slice mutably, which would invalidate every reference
+            // previously returned. Only the elements before `index` are reborrowed, shared.
+            //
+            // This is safe because the pushed element is at `index`, and the slice
+            // ends right before it.
+            #[cfg(not(feature = "safe-only"))]
+            let (before, item) = unsafe {
+                let ptr = self.0.as_mut_ptr();
+                (slice::from_raw_parts(ptr, index), &mut *ptr.add(index))
+            };
+            #[cfg(feature = "safe-only")]
+            let (before, item) = {
+                let (before, last) = self.0.split_at_mut(index);
+                (&*before, &mut last[0])
+            };
+            (index, Self::detach_mut(item, Token(())), Self::detach(before, Token(())))
+        }
+    }
+
+    detached! {
+        /// Pushes an element to the back of the `PushVec<T>`.
+        /// Returns a shared reference to the pushed element.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::from_vec(Vec::with_capacity(3));
+        /// let x = unsafe { vec.push_ref(1) };
+        /// let y = unsafe { vec.push_ref(2) };
+        /// let z = unsafe { vec.push_ref(3) };
+        /// assert_eq!((*x, *y, *z), (1, 2, 3));
+        /// ```
+        #[inline]
+        pub fn push_ref<'vec, 'a>(&'vec mut self, item: T) -> &'a T
+            where Self: Detach<'vec, 'a>,
+        {
+            self.push(item)
+        }
+    }
+
+    /// Returns `true` if `needle` is a prefix of the `PushVec<T>`.
+    /// This is the same as `<[T]>::starts_with()`.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec = push_vec![1, 2, 3];
+    /// assert!(vec.starts_with(&[1, 2]));
+    /// assert!(!vec.starts_with(&[2, 3]));
+    /// ```
+    #[inline]
+    pub fn starts_with(&self, needle: &[T]) -> bool
+        where T: PartialEq,
+    {
+        self.0.starts_with(needle)
+    }
+
+    /// Returns `true` if `needle` is a suffix of the `PushVec<T>`.
+    /// This is the same as `<[T]>::ends_with()`.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec = push_vec![1, 2, 3];
+    /// assert!(vec.ends_with(&[2, 3]));
+    /// assert!(!vec.ends_with(&[1, 2]));
+    /// ```
+    #[inline]
+    pub fn ends_with(&self, needle: &[T]) -> bool
+        where T: PartialEq,
+    {
+        self.0.ends_with(needle)
+    }
+
+    detached! {
+        /// Pushes an element to the back of the `PushVec<T>` if it passes
+        /// `validate`.
+        /// Returns a mutable reference to the pushed element, or the element and
+        /// the error if it was rejected, in which case the `PushVec<T>` is left
+        /// unchanged.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let positive = |x: &i32| if *x > 0 { Ok(()) } else { Err("not positive") };
+        /// let mut vec = PushVec::new();
+        /// assert_eq!(unsafe { vec.push_validated(1, positive) }, Ok(&mut 1));
+        /// assert_eq!(unsafe { vec.push_validated(-1, positive) }, Err((-1, "not positive")));
+        /// assert_eq!(vec, push_vec![1]);
+        /// ```
+        #[inline]
+        pub fn push_validated<'vec, 'a, E, F>(&'vec mut self, item: T, validate: F) -> Result<&'a mut T, (T, E)>
+            where Self: Detach<'vec, 'a>,
+                  F: FnOnce(&T) -> Result<(), E>,
+        {
+            match validate(&item) {
+                Ok(()) => Ok(self.push(item)),
+                Err(err) => Err((item, err)),
+            }
+        }
+    }
+
+    /// Returns the number of elements that satisfy the predicate.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec = push_vec![1, 2, 3, 4, 6];
+    /// assert_eq!(vec.count_matching(|x| x % 2 == 0), 3);
+    /// ```
+    #[inline]
+    pub fn count_matching<F>(&self, mut f: F) -> usize
+        where F: FnMut(&T) -> bool,
+    {
+        self.0.iter().filter(|x| f(x)).count()
+    }
+
+    /// Replaces the element at the given index, returning the old one.
+    /// The length of the `PushVec<T>` is unchanged.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = push_vec![1, 2, 3];
+    /// assert_eq!(vec.replace(1, 4), 2);
+    /// assert_eq!(vec, push_vec![1, 4, 3]);
+    /// ```
+    #[inline]
+    pub fn replace(&mut self, index: usize, value: T) -> T {
+        mem::replace(&mut self[index], value)
+    }
+
+    /// Appends the elements of an iterator to the `PushVec<T>`, returning an
+    /// error instead of aborting if the allocation fails.
+    ///
+    /// Capacity is reserved ahead using the iterator's `size_hint`, and again
+    /// whenever the hint turns out to be too low.
+    ///
+    /// # Errors
+    /// Fails if the capacity overflows or the allocator reports a failure. The
+    /// elements appended before the failure are kept.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = push_vec![1];
+    /// vec.try_extend([2, 3, 4]).unwrap();
+    /// vec.try_extend((5..).take_while(|&x| x < 7)).unwrap();
+    /// assert_eq!(vec, push_vec![1, 2, 3, 4, 5, 6]);
+    /// ```
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), TryReserveError>
+        where I: IntoIterator<Item = T>,
+    {
+        let mut iter = iter.into_iter();
+        self.grow(|vec| vec.try_reserve(iter.size_hint().0))?;
+        while let Some(item) = iter.next() {
+            if self.0.len() == self.0.capacity() {
+                self.grow(|vec| vec.try_reserve(iter.size_hint().0.saturating_add(1)))?;
+            }
+            self.0.push(item);
+        }
+        Ok(())
+    }
+
+    detached! {
+        /// Returns the first element and the rest of the elements, or `None` if
+        /// the `PushVec<T>` is empty.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(4);
+        /// vec.extend([1, 2, 3]);
+        /// let (first, rest) = unsafe { vec.split_first() }.unwrap();
+        /// unsafe { vec.push(4) };
+        /// assert_eq!((first, rest), (&1, &[2, 3][..]));
+        /// assert!(unsafe { PushVec::<i32>::new().split_first() }.is_none());
+        /// ```
+        #[inline]
+        pub fn split_first<'vec, 'a>(&'vec self) -> Option<(&'a T, &'a [T])>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_slice().split_first()
+        }
+    }
+
+    detached! {
+        /// Returns the first element and the rest of the elements mutably, or
+        /// `None` if the `PushVec<T>` is empty.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(4);
+        /// vec.extend([1, 2, 3]);
+        /// let (first, rest) = unsafe { vec.split_first_mut() }.unwrap();
+        /// unsafe { vec.push(4) };
+        /// *first = rest.iter().sum();
+        /// assert_eq!(vec, push_vec![5, 2, 3, 4]);
+        /// assert!(unsafe { PushVec::<i32>::new().split_first_mut() }.is_none());
+        /// ```
+        #[inline]
+        pub fn split_first_mut<'vec, 'a>(&'vec mut self) -> Option<(&'a mut T, &'a mut [T])>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_mut_slice().split_first_mut()
+        }
+    }
+
+    detached! {
+        /// Returns the last element and the rest of the elements, or `None` if
+        /// the `PushVec<T>` is empty.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(4);
+        /// vec.extend([1, 2, 3]);
+        /// let (last, rest) = unsafe { vec.split_last() }.unwrap();
+        /// unsafe { vec.push(4) };
+        /// assert_eq!((last, rest), (&3, &[1, 2][..]));
+        /// assert!(unsafe { PushVec::<i32>::new().split_last() }.is_none());
+        /// ```
+        #[inline]
+        pub fn split_last<'vec, 'a>(&'vec self) -> Option<(&'a T, &'a [T])>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_slice().split_last()
+        }
+    }
+
+    detached! {
+        /// Returns the last element and the rest of the elements mutably, or
+        /// `None` if the `PushVec<T>` is empty.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(4);
+        /// vec.extend([1, 2, 3]);
+        /// let (last, rest) = unsafe { vec.split_last_mut() }.unwrap();
+        /// unsafe { vec.push(4) };
+        /// *last = rest.iter().sum();
+        /// assert_eq!(vec, push_vec![1, 2, 3, 4]);
+        /// assert!(unsafe { PushVec::<i32>::new().split_last_mut() }.is_none());
+        /// ```
+        #[inline]
+        pub fn split_last_mut<'vec, 'a>(&'vec mut self) -> Option<(&'a mut T, &'a mut [T])>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_mut_slice().split_last_mut()
+        }
+    }
+
+    /// Consumes the `PushVec<T>`, splitting it into the elements that satisfy
+    /// the predicate and the elements that don't, in their original order.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec = push_vec![1, 2, 3, 4, 5];
+    /// let (evens, odds) = vec.partition_into(|x| x % 2 == 0);
+    /// assert_eq!(evens, push_vec![2, 4]);
+    /// assert_eq!(odds, push_vec![1, 3, 5]);
+    /// ```
+    #[inline]
+    pub fn partition_into<F>(self, pred: F) -> (PushVec<T>, PushVec<T>)
+        where F: FnMut(&T) -> bool,
+    {
+        let (matching, rest): (Vec<T>, Vec<T>) = self.0.into_iter().partition(pred);
+        (PushVec::from_vec(matching), PushVec::from_vec(rest))
+    }
+
+    detached! {
+        /// Inserts an element at the given index, shifting all the elements after
+        /// it to the right.
+        /// Returns a mutable reference to the inserted element.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// Shifting moves the elements, so this invalidates every reference to the
+        /// elements at and after `index`, as well as all references if the
+        /// `PushVec<T>` reallocates.
+        ///
+        /// # Panics
+        /// Panics if `index > len`.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let mut vec = push_vec![1, 3, 4];
+        /// let x = unsafe { vec.insert(1, 0) };
+        /// *x = 2;
+        /// assert_eq!(vec, push_vec![1, 2, 3, 4]);
+        /// ```
+        #[inline]
+        pub fn insert<'vec, 'a>(&'vec mut self, index: usize, element: T) -> &'a mut T
+            where Self: Detach<'vec, 'a>,
+        {
+            self.grow_one(|vec| vec.insert(index, element));
+            let item = self.element_mut(index);
+            Self::detach_mut(item, Token(()))
+        }
+    }
+
+    /// Returns a `Cursor<T>` pointing at the first element of the `PushVec<T>`.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = push_vec![1, 2];
+    /// let mut cursor = vec.cursor();
+    /// assert_eq!(cursor.current(), Some(&1));
+    /// ```
+    #[inline]
+    pub fn cursor(&mut self) -> Cursor<'_, T> {
+        Cursor { vec: self, index: 0 }
+    }
+
+    detached! {
+        /// Returns the element a `Handle<T>` refers to, or `None` if it's out of
+        /// bounds.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let vec = push_vec!['a', 'b'];
+        /// let (handle, _) = unsafe { vec.iter_handles() }.last().unwrap();
+        /// assert_eq!(unsafe { vec.resolve(handle) }, Some(&'b'));
+        /// ```
+        #[inline]
+        pub fn resolve<'vec, 'a>(&'vec self, handle: Handle<T>) -> Option<&'a T>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.get(handle.index)
+        }
+    }
+
+    detached! {
+        /// Returns the element a `Handle<T>` refers to mutably, or `None` if it's
+        /// out of bounds.
+        /// Doesn't borrow the `PushVec<T>`.
+        #[inline]
+        pub fn resolve_mut<'vec, 'a>(&'vec mut self, handle: Handle<T>) -> Option<&'a mut T>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.get_mut(handle.index)
+        }
+    }
+
+    detached! {
+        /// Returns an iterator over the elements of the `PushVec<T>` and handles
+        /// to them.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// use push_vec::Handle;
+        /// let vec = push_vec!["a", "b", "c"];
+        /// let handles: Vec<(Handle<&str>, &str)> = unsafe { vec.iter_handles() }
+        ///     .map(|(handle, x)| (handle, *x))
+        ///     .collect();
+        /// for (handle, x) in handles {
+        ///     assert_eq!(unsafe { vec.resolve(handle) }, Some(&x));
+        /// }
+        /// ```
+        #[inline]
+        pub fn iter_handles<'vec, 'a>(&'vec self) -> impl Iterator<Item = (Handle<T>, &'a T)>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.iter_indexed().map(|(index, x)| (Handle::new(index), x))
+        }
+    }
+
+    /// Appends clones of the elements of a slice to the `PushVec<T>`.
+    /// Returns a handle to each of the pushed elements.
+    ///
+    /// Room for all of them is reserved up front, so the buffer grows at most
+    /// once.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = push_vec!["a"];
+    /// let handles = vec.push_slice_handles(&["b", "c"]);
+    /// assert_eq!(handles[0].index(), 1);
+    /// let resolved: Vec<_> = handles.iter().map(|h| vec[h.index()]).collect();
+    /// assert_eq!(resolved, ["b", "c"]);
+    /// ```
+    #[inline]
+    pub fn push_slice_handles(&mut self, items: &[T]) -> Vec<Handle<T>>
+        where T: Clone,
+    {
+        let start = self.0.len();
+        self.grow(|vec| vec.extend_from_slice(items));
+        (start..self.0.len()).map(Handle::new).collect()
+    }
+
+    /// Reserves capacity for at least `total_len` elements in total. Does
+    /// nothing if the capacity is already sufficient.
+    ///
+    /// # Panics
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = PushVec::with_capacity(10);
+    /// vec.extend([1, 2, 3]);
+    /// let capacity = vec.capacity();
+    /// vec.reserve_total(5);
+    /// assert_eq!(vec.capacity(), capacity);
+    /// vec.reserve_total(20);
+    /// assert!(vec.capacity() >= 20);
+    /// ```
+    #[inline]
+    pub fn reserve_total(&mut self, total_len: usize) {
+        self.grow(|vec| vec.reserve(total_len.saturating_sub(vec.len())));
+    }
+
+    /// Converts the `PushVec<T>` into an array, if its length is exactly `N`.
+    /// Otherwise, the `PushVec<T>` is handed back unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// assert_eq!(push_vec![1, 2, 3].into_array(), Ok([1, 2, 3]));
+    /// assert_eq!(push_vec![1, 2, 3].into_array::<2>(), Err(push_vec![1, 2, 3]));
+    /// ```
+    #[inline]
+    pub fn into_array<const N: usize>(self) -> Result<[T; N], PushVec<T>> {
+        self.0.try_into().map_err(PushVec::from_vec)
+    }
+
+    detached! {
+        /// Pushes `T::default()` to the back of the `PushVec<T>`.
+        /// Returns a mutable reference to the pushed element, for initializing it
+        /// in place.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec: PushVec<Vec<i32>> = PushVec::with_capacity(2);
+        /// let first = unsafe { vec.push_default() };
+        /// unsafe { vec.push_default() }.push(3);
+        /// first.push(1);
+        /// first.push(2);
+        /// assert_eq!(vec, push_vec![vec![1, 2], vec![3]]);
+        /// ```
+        #[inline]
+        pub fn push_default<'vec, 'a>(&'vec mut self) -> &'a mut T
+            where Self: Detach<'vec, 'a>,
+                  T: Default,
+        {
+            self.push(T::default())
+        }
+    }
+
+    /// Converts the `PushVec<T>` into a `SealedVec<T>`, dropping any spare
+    /// capacity. Sealing may reallocate, which invalidates the references
+    /// previously returned by the `PushVec<T>`.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = PushVec::with_capacity(10);
+    /// vec.extend([1, 2, 3]);
+    /// let sealed = vec.seal();
+    /// assert_eq!(*sealed.into_boxed_slice(), [1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn seal(self) -> SealedVec<T> {
+        SealedVec(self.0.into_boxed_slice())
+    }
+
+    /// Consumes the `PushVec<T>` into an iterator over its elements, through
+    /// which the elements not yet consumed can be inspected with `as_slice`.
+    ///
+    /// This is the same iterator `into_iter` returns, named for pipelines that
+    /// consume from the front while peeking at the rest.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut iter = push_vec![1, 2, 3, 4].drain_into_iter();
+    /// assert_eq!(iter.next(), Some(1));
+    /// assert_eq!(iter.next(), Some(2));
+    /// assert_eq!(iter.as_slice(), &[3, 4]);
+    /// assert_eq!(iter.collect::<Vec<_>>(), vec![3, 4]);
+    /// ```
+    #[inline]
+    pub fn drain_into_iter(self) -> std::vec::IntoIter<T> {
+        self.0.into_iter()
+    }
+
+    /// Consumes the `PushVec<T>`, grouping its elements by the key function.
+    /// The elements of every group keep their original order.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let groups = push_vec![1, 2, 3, 4, 5].group_by_into(|x| x % 2 == 0);
+    /// assert_eq!(groups[&true], push_vec![2, 4]);
+    /// assert_eq!(groups[&false], push_vec![1, 3, 5]);
+    /// ```
+    pub fn group_by_into<K, F>(self, mut key: F) -> HashMap<K, PushVec<T>>
+        where K: Eq + Hash,
+              F: FnMut(&T) -> K,
+    {
+        let mut groups = HashMap::new();
+        for item in self.0 {
+            groups.entry(key(&item)).or_insert_with(PushVec::new).0.push(item);
+        }
+        groups
+    }
+
+    /// Consumes the `PushVec<T>`, splitting it into runs of consecutive
+    /// elements with the same key, in order. Unlike `group_by_into`, elements
+    /// with the same key that aren't next to each other end up in separate
+    /// groups.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let groups = push_vec![1, 1, 2, 2, 1].into_grouped(|x| *x);
+    /// assert_eq!(groups, [
+    ///     (1, push_vec![1, 1]),
+    ///     (2, push_vec![2, 2]),
+    ///     (1, push_vec![1]),
+    /// ]);
+    /// ```
+    #[inline]
+    pub fn into_grouped<K, F>(self, mut key: F) -> Vec<(K, PushVec<T>)>
+        where K: Eq,
+              F: FnMut(&T) -> K,
+    {
+        let mut groups: Vec<(K, PushVec<T>)> = Vec::new();
+        for item in self.0 {
+            let k = key(&item);
+            match groups.last_mut() {
+                Some((last, group)) if *last == k => group.0.push(item),
+                _ => groups.push((k, push_vec![item])),
+            }
+        }
+        groups
+    }
+
+    detached! {
+        /// Sets the element at the given index, first pushing `T::default()` up to
+        /// it if the `PushVec<T>` is too short.
+        /// Returns a mutable reference to the element.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// An existing element at `index` is always overwritten.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let mut vec = push_vec![1];
+        /// unsafe { vec.push_at(3, 4) };
+        /// assert_eq!(vec, push_vec![1, 0, 0, 4]);
+        /// *unsafe { vec.push_at(0, 5) } += 1;
+        /// assert_eq!(vec, push_vec![6, 0, 0, 4]);
+        /// ```
+        #[inline]
+        pub fn push_at<'vec, 'a>(&'vec mut self, index: usize, value: T) -> &'a mut T
+            where Self: Detach<'vec, 'a>,
+                  T: Default,
+        {
+            if index < self.0.len() {
+                let item = self.element_mut(index);
+                *item = value;
+                Self::detach_mut(item, Token(()))
+            } else {
+                self.grow(|vec| vec.resize_with(index, T::default));
+                self.push(value)
+            }
+        }
+    }
+
+    /// Consumes the `PushVec<T>` into an iterator over owned chunks of `n`
+    /// elements. The last chunk may be shorter.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec: PushVec<i32> = (1..8).collect();
+    /// let chunks: Vec<Vec<i32>> = vec.into_chunks(3).collect();
+    /// assert_eq!(chunks, [vec![1, 2, 3], vec![4, 5, 6], vec![7]]);
+    /// ```
+    pub fn into_chunks(self, n: usize) -> impl Iterator<Item = Vec<T>> {
+        assert!(n != 0, "chunk size must be non-zero");
+        let mut iter = self.0.into_iter();
+        iter::from_fn(move || {
+            let chunk: Vec<T> = iter.by_ref().take(n).collect();
+            if chunk.is_empty() {
+                None
+            } else {
+                Some(chunk)
+            }
+        })
+    }
+
+    /// Consumes the `PushVec<T>`, splitting it into `n` contiguous blocks
+    /// whose lengths differ by at most one, for handing out to `n` workers.
+    /// The longer blocks come first, and some blocks are empty if there are
+    /// fewer than `n` elements.
+    ///
+    /// Contiguous blocks keep neighbouring elements together, rather than
+    /// dealing them out round-robin, so every block is a range of the
+    /// original indices.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec: PushVec<i32> = (0..10).collect();
+    /// let blocks = vec.split_into_n(3);
+    /// assert_eq!(blocks, [push_vec![0, 1, 2, 3], push_vec![4, 5, 6], push_vec![7, 8, 9]]);
+    /// ```
+    #[inline]
+    pub fn split_into_n(self, n: usize) -> Vec<PushVec<T>> {
+        assert!(n != 0, "number of blocks must be non-zero");
+        let (base, extra) = (self.0.len() / n, self.0.len() % n);
+        let mut iter = self.0.into_iter();
+        (0..n)
+            .map(|i| {
+                let len = base + usize::from(i < extra);
+                iter.by_ref().take(len).collect()
+            })
+            .collect()
+    }
+
+    detached! {
+        /// Pushes an element to the back of the `PushVec<T>` if `gate` approves,
+        /// given the current elements.
+        /// Returns the index of the pushed element and a mutable reference to it,
+        /// or `None` if it was rejected, in which case the element is dropped.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// `gate` is given a slice of every element, so the mutable references
+        /// returned by earlier pushes alias it, and must not be used after this
+        /// call, whether or not the element was pushed.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let mut vec = push_vec![1, 2];
+        /// let below_ten = |items: &[i32]| items.iter().sum::<i32>() < 10;
+        /// assert_eq!(unsafe { vec.push_if(7, below_ten) }, Some((2, &mut 7)));
+        /// assert_eq!(unsafe { vec.push_if(1, below_ten) }, None);
+        /// assert_eq!(vec, push_vec![1, 2, 7]);
+        /// ```
+        #[inline]
+        pub fn push_if<'vec, 'a, F>(&'vec mut self, item: T, gate: F) -> Option<(usize, &'a mut T)>
+            where Self: Detach<'vec, 'a>,
+                  F: FnOnce(&[T]) -> bool,
+        {
+            if gate(&self.0) {
+                let index = self.0.len();
+                Some((index, self.push(item)))
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns a clone of the current elements, which is unaffected by later
+    /// pushes to and mutations of the `PushVec<T>`.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = push_vec![1, 2];
+    /// let snapshot = vec.snapshot();
+    /// vec.extend([3]);
+    /// vec[0] = 4;
+    /// assert_eq!(snapshot, [1, 2]);
+    /// ```
+    #[inline]
+    pub fn snapshot(&self) -> Vec<T>
+        where T: Clone,
+    {
+        self.0.clone()
+    }
+
+    detached! {
+        /// Pushes an element to the back of the `PushVec<T>`.
+        /// Returns a mutable reference to the pushed element, and a `StableToken`
+        /// identifying it by its address.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(3);
+        /// unsafe { vec.push(1) };
+        /// let (_, token) = unsafe { vec.push_token(2) };
+        /// unsafe { vec.push(3) };
+        /// assert_eq!(unsafe { vec.get_by_token(token) }, Some(&2));
+        /// ```
+        #[inline]
+        pub fn push_token<'vec, 'a>(&'vec mut self, item: T) -> (&'a mut T, StableToken)
+            where Self: Detach<'vec, 'a>,
+        {
+            let item = self.push(item);
+            let token = StableToken(item as *mut T as usize);
+            (item, token)
+        }
+    }
+
+    detached! {
+        /// Returns the element a `StableToken` identifies, or `None` if its
+        /// address isn't an element of the `PushVec<T>`.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// Tokens identify addresses, so they are only meaningful until the
+        /// `PushVec<T>` reallocates. Nothing else is recorded, so a token of
+        /// another `PushVec<T>` is only rejected if its address isn't in this one:
+        /// it is accepted if this buffer was allocated where the other one was, or
+        /// if the elements are zero-sized, since those all share one address.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(1);
+        /// let mut other = PushVec::with_capacity(1);
+        /// let (_, token) = unsafe { other.push_token(1) };
+        /// unsafe { vec.push(1) };
+        /// assert_eq!(unsafe { vec.get_by_token(token) }, None);
+        /// assert_eq!(unsafe { other.get_by_token(token) }, Some(&1));
+        /// ```
+        #[inline]
+        pub fn get_by_token<'vec, 'a>(&'vec self, token: StableToken) -> Option<&'a T>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.get(self.index_of_addr(token.0)?)
+        }
+    }
+
+    /// Returns the index of the element at the given address, if there is one.
+    fn index_of_addr(&self, addr: usize) -> Option<usize> {
+        let start = self.0.as_ptr() as usize;
+        let size = mem::size_of::<T>();
+        if size == 0 {
+            // All the elements share the same address.
+            return (addr == start && !self.0.is_empty()).then_some(0);
+        }
+        let offset = addr.checked_sub(start)?;
+        let index = offset / size;
+        (offset % size == 0 && index < self.0.len()).then_some(index)
+    }
+
+    /// Converts the `PushVec<T>` into a `SharedPushVec<T>`, which can be
+    /// pushed to from multiple threads.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let shared = push_vec![1].into_shared();
+    /// assert_eq!(shared.push(2), 2);
+    /// assert_eq!(shared.len(), 2);
+    /// ```
+    #[inline]
+    pub fn into_shared(self) -> SharedPushVec<T> {
+        SharedPushVec(Arc::new(Mutex::new(self)))
+    }
+
+    detached! {
+        /// Pushes an element to the back of the `PushVec<T>`.
+        /// Returns a mutable reference to the pushed element, and the index of
+        /// the element that was last before it, or `None` if it's the first.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::new();
+        /// assert_eq!(unsafe { vec.push_linked('a') }.1, None);
+        /// assert_eq!(unsafe { vec.push_linked('b') }.1, Some(0));
+        /// assert_eq!(unsafe { vec.push_linked('c') }.1, Some(1));
+        /// ```
+        #[inline]
+        pub fn push_linked<'vec, 'a>(&'vec mut self, item: T) -> (&'a mut T, Option<usize>)
+            where Self: Detach<'vec, 'a>,
+        {
+            let previous = self.0.len().checked_sub(1);
+            (self.push(item), previous)
+        }
+    }
+
+    detached! {
+        /// Returns the element at the given index together with the elements
+        /// before and after it, if they exist. Returns `None` if the index is out
+        /// of bounds.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let vec = push_vec![1, 2, 3];
+        /// assert_eq!(unsafe { vec.get_with_neighbors(1) }, Some((Some(&1), &2, Some(&3))));
+        /// assert_eq!(unsafe { vec.get_with_neighbors(0) }, Some((None, &1, Some(&2))));
+        /// assert_eq!(unsafe { vec.get_with_neighbors(2) }, Some((Some(&2), &3, None)));
+        /// assert_eq!(unsafe { vec.get_with_neighbors(3) }, None);
+        /// ```
+        #[inline]
+        pub fn get_with_neighbors<'vec, 'a>(&'vec self, index: usize) -> Option<(Option<&'a T>, &'a T, Option<&'a T>)>
+            where Self: Detach<'vec, 'a>,
+        {
+            let slice = self.as_slice();
+            let current = slice.get(index)?;
+            let previous = index.checked_sub(1).map(|i| &slice[i]);
+            Some((previous, current, slice.get(index + 1)))
+        }
+    }
+
+    /// Sets a callback that is called with the old and the new capacity
+    /// whenever the buffer of the `PushVec<T>` grows, which is when the
+    /// references previously returned by it are invalidated. Replaces the
+    /// previous callback, if any.
+    ///
+    /// The callback isn't cloned with the `PushVec<T>`, and growth through
+    /// `as_vec_mut` isn't reported. It must be `Send`, so that the
+    /// `PushVec<T>` can still be sent to other threads.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// use std::sync::{Arc, Mutex};
+    /// let log = Arc::new(Mutex::new(Vec::new()));
+    /// let mut vec = PushVec::with_capacity(2);
+    /// let callback_log = Arc::clone(&log);
+    /// vec.on_realloc(Box::new(move |old, new| {
+    ///     callback_log.lock().unwrap().push((old, new));
+    /// }));
+    /// vec.extend([1, 2]);
+    /// assert!(log.lock().unwrap().is_empty());
+    /// vec.extend([3]);
+    /// assert_eq!(*log.lock().unwrap(), [(2, vec.capacity())]);
+    /// ```
+    #[inline]
+    pub fn on_realloc(&mut self, callback: Box<dyn FnMut(usize, usize) + Send>) {
+        self.1.hooks().on_realloc = Some(OnRealloc(callback));
+    }
+
+    /// Runs an operation that may grow the buffer, reporting it to the
+    /// `on_realloc` callback if it did.
+    #[inline]
+    fn grow<R>(&mut self, f: impl FnOnce(&mut Vec<T>) -> R) -> R {
+        let old = self.0.capacity();
+        let result = f(&mut self.0);
+        let new = self.0.capacity();
+        if new > old {
+            if let Some(callback) = self.1.on_realloc() {
+                callback(old, new);
+            }
+        }
+        result
+    }
+
+    /// Sets the factor by which the capacity of the `PushVec<T>` is multiplied
+    /// when a push finds the buffer full, in place of the doubling of `Vec<T>`.
+    /// A smaller factor wastes less memory, at the cost of more frequent
+    /// reallocations. The factor is kept when the `PushVec<T>` is cloned.
+    ///
+    /// The capacity always grows by at least one element, and the allocator
+    /// may give more than asked for. If growing by the factor would exceed
+    /// `isize::MAX` bytes, the capacity grows as that of a `Vec<T>` would
+    /// instead. Only the methods that push a single element use the factor;
+    /// the others reserve as `Vec<T>` does.
+    ///
+    /// # Panics
+    /// Panics if the factor is less than 1, infinite or NaN.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = PushVec::with_capacity(10);
+    /// vec.set_growth_factor(1.5);
+    /// vec.extend(0..10);
+    /// unsafe { vec.push(10) };
+    /// assert!((15..20).contains(&vec.capacity()));
+    /// vec.extend(11..15);
+    /// unsafe { vec.push(15) };
+    /// assert!(vec.capacity() >= 22);
+    ///
+    /// // A factor too large to allocate for grows like a `Vec<T>`.
+    /// let mut vec = PushVec::new();
+    /// let mut std_vec = Vec::new();
+    /// vec.as_vec_mut().reserve_exact(1);
+    /// std_vec.reserve_exact(1);
+    /// vec.set_growth_factor(1e300);
+    /// unsafe { vec.push(1) };
+    /// unsafe { vec.push(2) };
+    /// std_vec.push(1);
+    /// std_vec.push(2);
+    /// assert_eq!(vec.capacity(), std_vec.capacity());
+    ///
+    /// let err = std::panic::catch_unwind(|| PushVec::<i32>::new().set_growth_factor(f64::INFINITY));
+    /// let message = err.unwrap_err().downcast_ref::<String>().unwrap().clone();
+    /// assert_eq!(message, "growth factor inf is not a finite number of at least 1");
+    /// ```
+    #[inline]
+    pub fn set_growth_factor(&mut self, factor: f64) {
+        assert!(
+            factor >= 1.0 && factor.is_finite(),
+            "growth factor {} is not a finite number of at least 1",
+            factor,
+        );
+        self.1.hooks().factor = Some(factor);
+    }
+
+    /// Like `grow`, for operations that add a single element: if the buffer is
+    /// full, first makes room according to the growth factor, if one is set.
+    #[inline]
+    fn grow_one<R>(&mut self, f: impl FnOnce(&mut Vec<T>) -> R) -> R {
+        let factor = self.1.factor();
+        self.grow(|vec| {
+            if let Some(factor) = factor {
+                if vec.len() == vec.capacity() {
+                    let max = isize::MAX as usize / mem::size_of::<T>().max(1);
+                    let target = vec.capacity() as f64 * factor;
+                    if target <= max as f64 {
+                        vec.reserve_exact((target as usize).max(vec.len() + 1) - vec.len());
+                    } else {
+                        vec.reserve(1);
+                    }
+                }
+            }
+            f(vec)
+        })
+    }
+
+    /// Returns a new `PushVec<T>` of clones of the elements in the range.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec = push_vec![1, 2, 3, 4, 5];
+    /// assert_eq!(vec.clone_range(1..3), push_vec![2, 3]);
+    /// assert_eq!(vec.clone_range(3..), push_vec![4, 5]);
+    /// ```
+    #[inline]
+    pub fn clone_range<R>(&self, r: R) -> PushVec<T>
+        where T: Clone,
+              R: RangeBounds<usize>,
+    {
+        let range = (r.start_bound().cloned(), r.end_bound().cloned());
+        PushVec::from_vec(self[range].to_vec())
+    }
+
+    /// Applies `f` to every element in place.
+    ///
+    /// The elements aren't moved, but they are all reborrowed mutably, so the
+    /// mutable references returned by earlier pushes must not be used after
+    /// this call.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = push_vec![1, 2, 3];
+    /// vec.map_in_place(|x| *x *= 2);
+    /// assert_eq!(vec, push_vec![2, 4, 6]);
+    /// ```
+    #[inline]
+    pub fn map_in_place<F>(&mut self, f: F)
+        where F: FnMut(&mut T),
+    {
+        self.0.iter_mut().for_each(f);
+    }
+
+    /// Returns a read-only view of the `PushVec<T>`, which can be cheaply
+    /// copied and passed around.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec = push_vec![1, 2, 3];
+    /// let view = vec.view();
+    /// assert_eq!(view.len(), 3);
+    /// ```
+    #[inline]
+    pub fn view(&self) -> PushVecView<'_, T> {
+        PushVecView(self)
+    }
+
+    detached! {
+        /// Pushes an element to the back of the `PushVec<T>`, unless an equal
+        /// element already exists.
+        /// Returns a mutable reference to the pushed element, or the index of the
+        /// existing one, in which case the element is dropped.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// This searches all the elements, so it takes linear time. The search
+        /// reads every element, so the mutable references returned by earlier
+        /// pushes must not be used after this call, whether or not the element was
+        /// pushed.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let mut vec = push_vec!["a", "b"];
+        /// assert_eq!(unsafe { vec.push_unique("c") }, Ok(&mut "c"));
+        /// assert_eq!(unsafe { vec.push_unique("b") }, Err(1));
+        /// assert_eq!(vec, push_vec!["a", "b", "c"]);
+        /// ```
+        #[inline]
+        pub fn push_unique<'vec, 'a>(&'vec mut self, item: T) -> Result<&'a mut T, usize>
+            where Self: Detach<'vec, 'a>,
+                  T: PartialEq,
+        {
+            match self.0.iter().position(|x| *x == item) {
+                Some(index) => Err(index),
+                None => Ok(self.push(item)),
+            }
+        }
+    }
+
+    /// Returns the index of the element a reference points at, or `None` if it
+    /// doesn't point into the `PushVec<T>`. This is the inverse of `get`.
+    ///
+    /// Zero-sized elements all share one address, so for them this returns the
+    /// first index.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+    #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+    /// use push_vec::prelude::*;
+    /// let mut vec = PushVec::with_capacity(3);
+    /// let x = unsafe { vec.push(1) };
+    /// let y = unsafe { vec.push(1) };
+    /// assert_eq!(vec.index_of_ref(x), Some(0));
+    /// assert_eq!(vec.index_of_ref(y), Some(1));
+    /// assert_eq!(vec.index_of_ref(&1), None);
+    /// ```
+    #[inline]
+    pub fn index_of_ref(&self, element: &T) -> Option<usize> {
+        self.index_of_addr(element as *const T as usize)
+    }
+
+    /// Returns a `Debug` representation of the `PushVec<T>` that also shows its
+    /// length, its capacity, and the address of every element.
+    ///
+    /// The addresses stay the same until the buffer grows, which helps to keep
+    /// track of which references are still valid.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = PushVec::with_capacity(4);
+    /// vec.extend([1, 2]);
+    /// let verbose = format!("{:?}", vec.debug_verbose());
+    /// assert!(verbose.contains("len: 2"));
+    /// assert!(verbose.contains("capacity: 4"));
+    /// assert!(verbose.contains(&format!("{:p}: 1", &vec[0])));
+    /// ```
+    #[inline]
+    pub fn debug_verbose(&self) -> impl Debug + '_
+        where T: Debug,
+    {
+        VerboseDebug(self)
+    }
+
+    detached! {
+        /// Returns an iterator over the pairs of consecutive elements of the
+        /// `PushVec<T>`, like `windows(2)` but yielding tuples.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let vec = push_vec![1, 2, 3];
+        /// let pairs: Vec<_> = unsafe { vec.pairs() }.collect();
+        /// assert_eq!(pairs, [(&1, &2), (&2, &3)]);
+        /// assert_eq!(unsafe { push_vec![1].pairs() }.count(), 0);
+        /// ```
+        #[inline]
+        pub fn pairs<'vec, 'a>(&'vec self) -> impl DoubleEndedIterator<Item = (&'a T, &'a T)>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_slice().windows(2).map(|pair| (&pair[0], &pair[1]))
+        }
+    }
+
+    /// Pairs up the elements of the `PushVec<T>` with those of another,
+    /// stopping at the end of the shorter one.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let zipped = push_vec![1, 2, 3].zip(push_vec!['a', 'b']);
+    /// assert_eq!(zipped, push_vec![(1, 'a'), (2, 'b')]);
+    /// ```
+    #[inline]
+    pub fn zip<U>(self, other: PushVec<U>) -> PushVec<(T, U)> {
+        self.0.into_iter().zip(other.0).collect()
+    }
+
+    /// Concatenates the elements of every element of the `PushVec<T>`,
+    /// flattening it one level.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let nested = push_vec![push_vec![1, 2], push_vec![], push_vec![3]];
+    /// assert_eq!(nested.flatten(), push_vec![1, 2, 3]);
+    /// let words = push_vec![vec!['a', 'b'], vec!['c']];
+    /// assert_eq!(words.flatten(), push_vec!['a', 'b', 'c']);
+    /// ```
+    #[inline]
+    pub fn flatten<U>(self) -> PushVec<U>
+        where T: IntoIterator<Item = U>,
+    {
+        self.0.into_iter().flatten().collect()
+    }
+
+    /// Records the current length of the `PushVec<T>`, to later get or discard
+    /// the elements pushed since.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = PushVec::with_capacity(4);
+    /// vec.extend([1]);
+    /// let mark = vec.mark();
+    /// vec.extend([2, 3]);
+    /// assert_eq!(mark.position(), 1);
+    /// assert_eq!(unsafe { vec.since_mark(mark) }, &[2, 3]);
+    /// ```
+    #[inline]
+    pub fn mark(&self) -> Mark {
+        Mark(self.0.len())
+    }
+
+    detached! {
+        /// Returns a slice of the elements pushed since a `Mark` was recorded.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Panics
+        /// Panics if the `PushVec<T>` is shorter than when the mark was recorded,
+        /// which happens if the mark comes from another `PushVec<T>`.
+        #[inline]
+        pub fn since_mark<'vec, 'a>(&'vec self, mark: Mark) -> &'a [T]
+            where Self: Detach<'vec, 'a>,
+        {
+            &self.as_slice()[mark.0..]
+        }
+    }
+
+    /// Discards the elements pushed since a `Mark` was recorded, dropping them
+    /// and bringing the `PushVec<T>` back to the length it had then. The
+    /// capacity is kept, so the space can be reused for the next batch.
+    ///
+    /// Since `push` and the like don't borrow the `PushVec<T>`, the borrow
+    /// checker can't tell whether references to the discarded elements are
+    /// still held, so this is unsafe even though it's a plain truncation.
+    ///
+    /// # Safety
+    /// No reference to an element pushed since the mark may be used after
+    /// the call.
+    ///
+    /// # Panics
+    /// Panics if the `PushVec<T>` is shorter than when the mark was recorded.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = PushVec::with_capacity(8);
+    /// vec.extend([1]);
+    /// let mark = vec.mark();
+    /// vec.extend([2, 3, 4]);
+    /// // No references to the batch are held.
+    /// unsafe { vec.rewind(mark) };
+    /// assert_eq!(vec, push_vec![1]);
+    /// assert_eq!(vec.capacity(), 8);
+    /// ```
+    #[inline]
+    pub unsafe fn rewind(&mut self, mark: Mark) {
+        assert!(
+            mark.0 <= self.0.len(),
+            "mark at length {} is past the end of PushVec of length {}",
+            mark.0,
+            self.0.len(),
+        );
+        self.0.truncate(mark.0);
+    }
+
+    detached! {
+        /// Reserves room for at least `n` more elements, and returns the first `n`
+        /// slots past the end of the `PushVec<T>` to be written to directly.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// The slots aren't part of the `PushVec<T>` until `assume_init_pushed` is
+        /// called, and pushing before then writes over them. Reserving may
+        /// reallocate, which invalidates references to the elements.
+        ///
+        /// # Panics
+        /// Panics if the new capacity exceeds `isize::MAX` bytes.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let mut vec = push_vec![1];
+        /// let slots = unsafe { vec.reserve_uninit(3) };
+        /// for (i, slot) in slots.iter_mut().enumerate() {
+        ///     slot.write(i * 10);
+        /// }
+        /// // The first three slots were written to.
+        /// unsafe { vec.assume_init_pushed(3) };
+        /// assert_eq!(vec, push_vec![1, 0, 10, 20]);
+        /// ```
+        #[inline]
+        pub fn reserve_uninit<'vec, 'a>(&'vec mut self, n: usize) -> &'a mut [MaybeUninit<T>]
+            where Self: Detach<'vec, 'a>,
+        {
+            self.grow(|vec| vec.reserve(n));
+            Self::detach_mut(&mut self.0.spare_capacity_mut()[..n], Token(()))
+        }
+    }
+
+    /// Makes the first `n` slots past the end of the `PushVec<T>` part of it,
+    /// as if they had been pushed. This is the second half of
+    /// `reserve_uninit`.
+    ///
+    /// # Safety
+    /// The slots must have been initialized, for example through the slice
+    /// returned by `reserve_uninit`.
+    ///
+    /// # Panics
+    /// Panics if `n` exceeds the spare capacity of the `PushVec<T>`.
+    #[inline]
+    pub unsafe fn assume_init_pushed(&mut self, n: usize) {
+        assert!(
+            n <= self.spare_capacity(),
+            "{} pushed elements exceed the spare capacity of {}",
+            n,
+            self.spare_capacity(),
+        );
+        self.0.set_len(self.0.len() + n);
+    }
+
+    /// Returns `true` if the `PushVec<T>` holds the same elements as another,
+    /// each the same number of times, in any order.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec = push_vec![1, 2, 2, 3];
+    /// assert!(vec.eq_unordered(&push_vec![2, 3, 1, 2]));
+    /// assert!(!vec.eq_unordered(&push_vec![1, 2, 3, 3]));
+    /// assert!(!vec.eq_unordered(&push_vec![1, 2, 3]));
+    /// ```
+    #[inline]
+    pub fn eq_unordered(&self, other: &PushVec<T>) -> bool
+        where T: Eq + Hash,
+    {
+        if self.0.len() != other.0.len() {
+            return false;
+        }
+        let mut counts = HashMap::new();
+        for x in &self.0 {
+            *counts.entry(x).or_insert(0usize) += 1;
+        }
+        for x in &other.0 {
+            match counts.get_mut(x) {
+                Some(0) | None => return false,
+                Some(count) => *count -= 1,
+            }
+        }
+        true
+    }
+
+    detached! {
+        /// Walks the elements of the `PushVec<T>` with an accumulator, pushing an
+        /// element derived from each one, as with `Iterator::scan`.
+        /// Returns a mutable slice of the pushed elements.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// Room for all the new elements is reserved up front, so the buffer
+        /// doesn't move while the existing elements are being read.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let mut vec = push_vec![1, 2, 3];
+        /// let sums = unsafe {
+        ///     vec.scan_push(0, |sum, x| {
+        ///         *sum += x;
+        ///         *sum
+        ///     })
+        /// };
+        /// assert_eq!(sums, &[1, 3, 6]);
+        /// assert_eq!(vec, push_vec![1, 2, 3, 1, 3, 6]);
+        /// ```
+        #[inline]
+        pub fn scan_push<'vec, 'a, S, F>(&'vec mut self, init: S, mut f: F) -> &'a mut [T]
+            where Self: Detach<'vec, 'a>,
+                  F: FnMut(&mut S, &T) -> T,
+        {
+            let len = self.0.len();
+            self.grow(|vec| vec.reserve(len));
+            let mut state = init;
+            for i in 0..len {
+                let item = f(&mut state, &self.0[i]);
+                self.0.push(item);
+            }
+            let pushed = self.range_mut(len..len * 2);
+            Self::detach_mut(pushed, Token(()))
+        }
+    }
+
+    /// Converts the `PushVec<T>` into an iterator over its elements and their
+    /// indices.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let pairs: Vec<_> = push_vec!['a', 'b'].into_iter_indexed().collect();
+    /// assert_eq!(pairs, [(0, 'a'), (1, 'b')]);
+    /// ```
+    #[inline]
+    pub fn into_iter_indexed(self) -> impl DoubleEndedIterator<Item = (usize, T)> + ExactSizeIterator {
+        self.0.into_iter().enumerate()
+    }
+
+    /// Checks every element of the `PushVec<T>` and its index with `validate`,
+    /// stopping at the first failure.
+    ///
+    /// # Errors
+    /// Returns the index of the first element that failed, along with its
+    /// error.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let positive = |_, x: &i32| if *x > 0 { Ok(()) } else { Err("not positive") };
+    /// assert_eq!(push_vec![1, 2, 3].validate_all(positive), Ok(()));
+    /// assert_eq!(push_vec![1, -2, -3].validate_all(positive), Err((1, "not positive")));
+    /// ```
+    #[inline]
+    pub fn validate_all<E, F>(&self, mut validate: F) -> Result<(), (usize, E)>
+        where F: FnMut(usize, &T) -> Result<(), E>,
+    {
+        for (index, x) in self.0.iter().enumerate() {
+            validate(index, x).map_err(|err| (index, err))?;
+        }
+        Ok(())
+    }
+
+    /// Returns a clone of the `PushVec<T>` without spare capacity, to keep an
+    /// archived copy in the least memory.
+    ///
+    /// `clone` makes no promise about the capacity of the clone, while this
+    /// shrinks it to the length, so pushing to the clone reallocates.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = PushVec::with_capacity(10);
+    /// vec.extend([1, 2, 3]);
+    /// let compact = vec.clone_compact();
+    /// assert_eq!(compact, vec);
+    /// assert_eq!(compact.capacity(), compact.len());
+    /// ```
+    #[inline]
+    pub fn clone_compact(&self) -> PushVec<T>
+        where T: Clone,
+    {
+        let mut clone = self.clone();
+        clone.0.shrink_to_fit();
+        clone
+    }
+
+    detached! {
+        /// Returns a slice of the last `n` elements of the `PushVec<T>`, or of all
+        /// of them if there are fewer.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let vec = push_vec![1, 2, 3];
+        /// assert_eq!(unsafe { vec.last_n(2) }, &[2, 3]);
+        /// assert_eq!(unsafe { vec.last_n(3) }, &[1, 2, 3]);
+        /// assert_eq!(unsafe { vec.last_n(5) }, &[1, 2, 3]);
+        /// ```
+        #[inline]
+        pub fn last_n<'vec, 'a>(&'vec self, n: usize) -> &'a [T]
+            where Self: Detach<'vec, 'a>,
+        {
+            let slice = self.as_slice();
+            &slice[slice.len().saturating_sub(n)..]
+        }
+    }
+
+    detached! {
+        /// Returns a copy of the elements of the `PushVec<T>` as they are now,
+        /// along with a mutable iterator over the elements, for updating each one
+        /// based on all the others.
+        /// The iterator doesn't borrow the `PushVec<T>`.
+        ///
+        /// A slice of the elements couldn't be read while they are being mutated
+        /// through the iterator without aliasing, so the snapshot is an owned
+        /// clone, and it doesn't see the updates.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let mut vec = push_vec![1, 2, 3];
+        /// let (snapshot, iter) = unsafe { vec.iter_mut_with_snapshot() };
+        /// for (i, x) in iter.enumerate() {
+        ///     // Every element becomes the sum of the others, before any update.
+        ///     *x = snapshot.iter().sum::<i32>() - snapshot[i];
+        /// }
+        /// assert_eq!(vec, push_vec![5, 4, 3]);
+        /// ```
+        #[inline]
+        pub fn iter_mut_with_snapshot<'vec, 'a>(&'vec mut self) -> (Vec<T>, impl Iterator<Item = &'a mut T>)
+            where Self: Detach<'vec, 'a>,
+                  T: Clone,
+        {
+            (self.snapshot(), self.iter_mut())
+        }
+    }
+
+    /// Returns the number of distinct elements in the `PushVec<T>`.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// assert_eq!(push_vec![1, 1, 2, 3, 3].count_distinct(), 3);
+    /// assert_eq!(PushVec::<i32>::new().count_distinct(), 0);
+    /// ```
+    #[inline]
+    pub fn count_distinct(&self) -> usize
+        where T: Eq + Hash,
+    {
+        self.0.iter().collect::<HashSet<_>>().len()
+    }
+
+    /// Returns a `LazyExtend`, a guard through which the elements of an
+    /// iterator are pulled into the `PushVec<T>` one at a time, so that a
+    /// large or unbounded source isn't drained up front.
+    ///
+    /// This appends nothing by itself: an element is only pushed when it is
+    /// pulled through the guard, and each pull may reallocate as `push` does.
+    /// The elements that were never pulled when the guard is dropped aren't
+    /// appended, and the `PushVec<T>` doesn't keep the iterator.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = push_vec![0];
+    /// let mut lazy = vec.extend_lazy(1..);
+    /// assert_eq!(lazy.get(3), Some(&3));
+    /// drop(lazy);
+    /// assert_eq!(vec, push_vec![0, 1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn extend_lazy<I: IntoIterator<Item = T>>(&mut self, iter: I) -> LazyExtend<'_, T, I::IntoIter> {
+        LazyExtend { vec: self, iter: iter.into_iter() }
+    }
+
+    detached! {
+        /// Returns the smallest element of the `PushVec<T>`, or `None` if it's
+        /// empty. If several are equally small, the first one is returned.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// This isn't named `min`, which would be shadowed by `Ord::min`, as
+        /// comparing whole `PushVec<T>`s.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(4);
+        /// vec.extend([3, 1, 2]);
+        /// let min = unsafe { vec.min_element() }.unwrap();
+        /// unsafe { vec.push(0) };
+        /// assert_eq!(*min, 1);
+        /// assert_eq!(unsafe { vec.min_element() }, Some(&0));
+        /// ```
+        #[inline]
+        pub fn min_element<'vec, 'a>(&'vec self) -> Option<&'a T>
+            where Self: Detach<'vec, 'a>,
+                  T: Ord,
+        {
+            self.as_slice().iter().min()
+        }
+    }
+
+    detached! {
+        /// Returns the largest element of the `PushVec<T>`, or `None` if it's
+        /// empty. If several are equally large, the last one is returned.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// assert_eq!(unsafe { push_vec![3, 1, 2].max_element() }, Some(&3));
+        /// assert_eq!(unsafe { PushVec::<i32>::new().max_element() }, None);
+        /// ```
+        #[inline]
+        pub fn max_element<'vec, 'a>(&'vec self) -> Option<&'a T>
+            where Self: Detach<'vec, 'a>,
+                  T: Ord,
+        {
+            self.as_slice().iter().max()
+        }
+    }
+
+    detached! {
+        /// Returns the element of the `PushVec<T>` with the smallest key, or
+        /// `None` if it's empty. If several are equally small, the first one is
+        /// returned.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// struct Player { name: &'static str, score: u32 }
+        /// let vec = push_vec![
+        ///     Player { name: "a", score: 3 },
+        ///     Player { name: "b", score: 1 },
+        ///     Player { name: "c", score: 1 }
+        /// ];
+        /// assert_eq!(unsafe { vec.min_by_key(|p| p.score) }.unwrap().name, "b");
+        /// ```
+        #[inline]
+        pub fn min_by_key<'vec, 'a, K, F>(&'vec self, mut f: F) -> Option<&'a T>
+            where Self: Detach<'vec, 'a>,
+                  K: Ord,
+                  F: FnMut(&T) -> K,
+        {
+            self.as_slice().iter().min_by_key(|x| f(x))
+        }
+    }
+
+    detached! {
+        /// Returns the element of the `PushVec<T>` with the largest key, or `None`
+        /// if it's empty. If several are equally large, the last one is returned.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// struct Player { name: &'static str, score: u32 }
+        /// let vec = push_vec![
+        ///     Player { name: "a", score: 3 },
+        ///     Player { name: "b", score: 1 },
+        ///     Player { name: "c", score: 3 }
+        /// ];
+        /// assert_eq!(unsafe { vec.max_by_key(|p| p.score) }.unwrap().name, "c");
+        /// ```
+        #[inline]
+        pub fn max_by_key<'vec, 'a, K, F>(&'vec self, mut f: F) -> Option<&'a T>
+            where Self: Detach<'vec, 'a>,
+                  K: Ord,
+                  F: FnMut(&T) -> K,
+        {
+            self.as_slice().iter().max_by_key(|x| f(x))
+        }
+    }
+
+    detached! {
+        /// Pushes an element to the back of the `PushVec<T>`.
+        /// Returns a mutable reference to the pushed element, and the new length.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let mut vec = push_vec![1];
+        /// let (x, len) = unsafe { vec.push_len(2) };
+        /// assert_eq!((*x, len), (2, 2));
+        /// let (_, len) = unsafe { vec.push_len(3) };
+        /// assert_eq!(len, 3);
+        /// ```
+        #[inline]
+        pub fn push_len<'vec, 'a>(&'vec mut self, item: T) -> (&'a mut T, usize)
+            where Self: Detach<'vec, 'a>,
+        {
+            let len = self.0.len() + 1;
+            (self.push(item), len)
+        }
+    }
+
+    /// Returns the index the next pushed element will have, which is the
+    /// length of the `PushVec<T>`.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = push_vec!['a', 'b'];
+    /// let next = vec.next_index();
+    /// assert_eq!(next, vec.len());
+    /// let (index, _) = unsafe { vec.push_with_index('c') };
+    /// assert_eq!(index, next);
+    /// ```
+    #[inline]
+    pub fn next_index(&self) -> usize {
+        self.0.len()
+    }
+
+    detached! {
+        /// Pushes an element to the back of the `PushVec<T>`.
+        /// Returns the index of the pushed element and a mutable reference to it.
+        /// Doesn't borrow the `PushVec<T>`.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(2);
+        /// let (i, x) = unsafe { vec.push_with_index(1) };
+        /// let (j, _) = unsafe { vec.push_with_index(2) };
+        /// *x = 3;
+        /// assert_eq!((i, j), (0, 1));
+        /// assert_eq!(vec, push_vec![3, 2]);
+        /// ```
+        #[inline]
+        pub fn push_with_index<'vec, 'a>(&'vec mut self, item: T) -> (usize, &'a mut T)
+            where Self: Detach<'vec, 'a>,
+        {
+            let index = self.next_index();
+            (index, self.push(item))
+        }
+    }
+}
+
+/// Plain old data that can be encoded as its raw bytes.
+///
+/// # Safety
+/// The type must have no padding bytes, and every bit pattern of its size
+/// must be a valid value.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($t:ty),*) => {
+        $(unsafe impl Pod for $t {})*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+impl<T: Pod> PushVec<T> {
+    /// Writes the `PushVec<T>` in a compact binary format: the length as a
+    /// little-endian `u64`, followed by the raw bytes of every element.
+    ///
+    /// The elements are written in native byte order, so the format is only
+    /// portable between platforms of the same endianness.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec: PushVec<u16> = push_vec![1, 2, 3];
+    /// let mut bytes = Vec::new();
+    /// vec.encode(&mut bytes).unwrap();
+    /// assert_eq!(bytes.len(), 8 + 3 * 2);
+    /// ```
+    pub fn encode(&self, w: &mut impl Write) -> io::Result<()> {
+        w.write_all(&(self.len() as u64).to_le_bytes())?;
+        // This is safe because `Pod` types have no padding.
+        let bytes = unsafe {
+            slice::from_raw_parts(
+                self.0.as_ptr() as *const u8,
+                self.len() * mem::size_of::<T>(),
+            )
+        };
+        w.write_all(bytes)
+    }
+
+    /// Reads a `PushVec<T>` written by `encode`.
+    ///
+    /// # Errors
+    /// Fails if the reader fails, or ends before all the elements were read.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec: PushVec<f64> = push_vec![1.5, -2.0, 3.25];
+    /// let mut bytes = Vec::new();
+    /// vec.encode(&mut bytes).unwrap();
+    /// assert_eq!(PushVec::decode(&mut bytes.as_slice()).unwrap(), vec);
+    /// // Missing elements are an error.
+    /// assert!(PushVec::<f64>::decode(&mut &bytes[..12]).is_err());
+    /// ```
+    pub fn decode(r: &mut impl Read) -> io::Result<Self> {
+        let mut len = [0; 8];
+        r.read_exact(&mut len)?;
+        let len = usize::try_from(u64::from_le_bytes(len))
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        // Don't trust the length for the allocation, the input may be short.
+        let mut vec = PushVec::with_capacity(len.min(1024));
+        for _ in 0..len {
+            // This is safe because every bit pattern is a valid `Pod` value.
+            let mut item: T = unsafe { mem::zeroed() };
+            let bytes = unsafe {
+                slice::from_raw_parts_mut(
+                    &mut item as *mut T as *mut u8,
+                    mem::size_of::<T>(),
+                )
+            };
+            r.read_exact(bytes)?;
+            vec.push_mut(item);
+        }
+        Ok(vec)
+    }
+}
+
+impl PushVec<u8> {
+    /// Reads all the bytes until the end of `r`, and appends them to the
+    /// `PushVec<u8>`.
+    /// Returns the number of bytes read.
+    ///
+    /// Reading may reallocate, which invalidates references to the elements
+    /// unless the capacity was reserved up front.
+    ///
+    /// # Errors
+    /// Fails if reading fails. The bytes read before the failure are kept.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// use std::io::Cursor;
+    /// let mut vec = push_vec![b'a'];
+    /// let read = vec.extend_from_reader(&mut Cursor::new(b"bcd")).unwrap();
+    /// assert_eq!(read, 3);
+    /// assert_eq!(&vec[..], b"abcd");
+    /// ```
+    #[inline]
+    pub fn extend_from_reader(&mut self, r: &mut impl Read) -> io::Result<usize> {
+        self.grow(|vec| r.read_to_end(vec))
+    }
+
+    detached! {
+        /// Appends the UTF-8 bytes of a string to the `PushVec<u8>`.
+        /// Returns a slice of the appended bytes.
+        /// Doesn't borrow the `PushVec<u8>`.
+        ///
+        /// # Example
+        #[cfg_attr(feature = "safe-only", doc = "```ignore")]
+        #[cfg_attr(not(feature = "safe-only"), doc = "```")]
+        /// use push_vec::prelude::*;
+        /// let mut vec = PushVec::with_capacity(16);
+        /// let hello = unsafe { vec.push_str("hello, ") };
+        /// let world = unsafe { vec.push_str("world") };
+        /// assert_eq!(hello, b"hello, ");
+        /// assert_eq!(world, b"world");
+        /// assert_eq!(unsafe { vec.as_slice() }, b"hello, world");
+        /// ```
+        #[inline]
+        pub fn push_str<'vec, 'a>(&'vec mut self, s: &str) -> &'a [u8]
+            where Self: Detach<'vec, 'a>,
+        {
+            let start = self.0.len();
+            self.grow(|vec| vec.extend_from_slice(s.as_bytes()));
+            let bytes = &*self.range_mut(start..start + s.len());
+            Self::detach(bytes, Token(()))
+        }
+    }
+}
+
+/// Soft deletion, for `PushVec<T>`s whose elements can't be removed without
+/// invalidating references and indices: a `None` element is a tombstone.
+impl<T> PushVec<Option<T>> {
+    /// Replaces the element at the given index with a tombstone, returning it.
+    /// Returns `None` if it was already a tombstone.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = push_vec![Some(1), Some(2)];
+    /// assert_eq!(vec.tombstone(0), Some(1));
+    /// assert_eq!(vec.tombstone(0), None);
+    /// assert_eq!(vec, push_vec![None, Some(2)]);
+    /// ```
+    #[inline]
+    pub fn tombstone(&mut self, index: usize) -> Option<T> {
+        self[index].take()
+    }
+
+    /// Consumes the `PushVec<Option<T>>`, rebuilding it without tombstones.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec = push_vec![Some(1), None, Some(3), None];
+    /// assert_eq!(vec.compact_into(), push_vec![1, 3]);
+    /// ```
+    #[inline]
+    pub fn compact_into(self) -> PushVec<T> {
+        self.0.into_iter().flatten().collect()
+    }
+}
+
+impl<A, B> PushVec<(A, B)> {
+    /// Splits a `PushVec<(A, B)>` of pairs into a `PushVec<A>` of the first
+    /// elements and a `PushVec<B>` of the second ones. This is the inverse of
+    /// `zip`.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let (numbers, letters) = push_vec![(1, 'a'), (2, 'b')].unzip();
+    /// assert_eq!(numbers, push_vec![1, 2]);
+    /// assert_eq!(letters, push_vec!['a', 'b']);
+    /// ```
+    #[inline]
+    pub fn unzip(self) -> (PushVec<A>, PushVec<B>) {
+        let (a, b) = self.0.into_iter().unzip();
+        (PushVec::from_vec(a), PushVec::from_vec(b))
+    }
+}
+
+/// A builder for a `PushVec<T>` that never reallocates.
+///
+/// Since the storage is allocated up front, references returned by `push` stay
+/// valid until the builder is dropped or built.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// let mut builder = PushVec::builder(3);
+/// let first: *const i32 = unsafe { builder.push(1) };
+/// unsafe { builder.push(2) };
+/// unsafe { builder.push(3) };
+/// let vec = builder.build();
+/// // The elements were never moved.
+/// assert_eq!(unsafe { vec.as_slice() }.as_ptr(), first);
+/// assert_eq!(vec, push_vec![1, 2, 3]);
+/// ```
+#[derive(Debug)]
+pub struct PushVecBuilder<T> {
+    vec: PushVec<T>,
+    capacity: usize,
+}
+
+impl<T> PushVecBuilder<T> {
+    /// Creates a new, empty `PushVecBuilder<T>` that can push up to
+    /// `capacity` elements.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let builder: PushVecBuilder<i32> = PushVecBuilder::new(10);
+    /// assert_eq!(builder.capacity(), 10);
+    /// ```
+    #[inline]
+    pub fn new(capacity: usize) -> Self {
+        PushVecBuilder {
+            vec: PushVec::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Returns the number of elements pushed so far.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns `true` if no elements were pushed yet.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Returns the maximum number of elements that can be pushed.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    detached! {
+        /// Pushes an element to the back of the builder.
+        /// Returns a mutable reference to the pushed element.
+        /// Doesn't borrow the `PushVecBuilder<T>`.
+        ///
+        /// # Panics
+        /// Panics if the builder is already full.
+        ///
+        /// ```should_panic
+        /// use push_vec::prelude::*;
+        /// let mut builder = PushVec::builder(1);
+        /// unsafe { builder.push(1) };
+        /// unsafe { builder.push(2) };
+        /// ```
+        #[inline]
+        pub fn push<'b, 'a>(&'b mut self, item: T) -> &'a mut T
+            where PushVec<T>: Detach<'b, 'a>,
+        {
+            assert!(
+                self.len() < self.capacity,
+                "PushVecBuilder capacity of {} exceeded",
+                self.capacity,
+            );
+            self.vec.push(item)
+        }
+    }
+
+    /// Finishes building, returning the `PushVec<T>`.
+    #[inline]
+    pub fn build(self) -> PushVec<T> {
+        self.vec
+    }
+}
+
+/// A cursor over the elements of a `PushVec<T>`, which can push new elements
+/// to the back without losing its position.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// let mut vec = push_vec![1, 2, 3];
+/// let mut cursor = vec.cursor();
+/// // Every odd element is doubled, and the double is queued to be visited.
+/// while let Some(x) = cursor.current_mut() {
+///     if *x % 2 == 1 {
+///         let double = *x * 2;
+///         cursor.push(double);
+///     }
+///     cursor.move_next();
+/// }
+/// assert_eq!(vec, push_vec![1, 2, 3, 2, 6]);
+/// ```
+#[derive(Debug)]
+pub struct Cursor<'a, T> {
+    vec: &'a mut PushVec<T>,
+    index: usize,
+}
+
+impl<'a, T> Cursor<'a, T> {
+    /// Returns the index of the element the cursor points at. This is equal to
+    /// the length of the `PushVec<T>` when the cursor is past the end.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns a reference to the element the cursor points at, or `None` if
+    /// the cursor is past the end.
+    #[inline]
+    pub fn current(&self) -> Option<&T> {
+        self.vec.0.get(self.index)
+    }
+
+    /// Returns a mutable reference to the element the cursor points at, or
+    /// `None` if the cursor is past the end.
+    #[inline]
+    pub fn current_mut(&mut self) -> Option<&mut T> {
+        self.vec.0.get_mut(self.index)
+    }
+
+    /// Moves the cursor to the next element.
+    /// Returns `false` if the cursor was already past the end.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = push_vec![1];
+    /// let mut cursor = vec.cursor();
+    /// assert!(cursor.move_next());
+    /// assert_eq!(cursor.current(), None);
+    /// assert!(!cursor.move_next());
+    /// // Pushing makes the cursor point at the new element.
+    /// cursor.push(2);
+    /// assert_eq!(cursor.current(), Some(&2));
+    /// ```
+    #[inline]
+    pub fn move_next(&mut self) -> bool {
+        if self.index < self.vec.len() {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Pushes an element to the back of the `PushVec<T>`, without moving the
+    /// cursor.
+    /// Returns a mutable reference to the pushed element.
+    ///
+    /// Like `PushVec::push`, this grows a full buffer by the growth factor, if
+    /// one is set.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let mut vec = PushVec::new();
+    /// vec.as_vec_mut().reserve_exact(10);
+    /// vec.extend(0..10);
+    /// vec.set_growth_factor(1.1);
+    /// vec.cursor().push(10);
+    /// assert_eq!(vec.capacity(), 11);
+    /// ```
+    #[inline]
+    pub fn push(&mut self, item: T) -> &mut T {
+        self.vec.grow_one(|vec| vec.push(item));
+        self.vec.0.last_mut().unwrap()
+    }
+}
+
+/// A guard that pulls the elements of an iterator into a `PushVec<T>` as they
+/// are accessed through it. Dropping it drops the rest of the iterator, without
+/// appending it. Created by `PushVec::extend_lazy`.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// let mut vec = PushVec::new();
+/// let mut pulled = 0;
+/// let mut lazy = vec.extend_lazy((0..100).inspect(|_| pulled += 1));
+/// assert_eq!(lazy.get(1), Some(&1));
+/// assert_eq!(lazy.get(0), Some(&0));
+/// assert_eq!(lazy.len(), 2);
+/// assert_eq!(lazy.finish(), 100);
+/// assert_eq!(pulled, 100);
+/// assert_eq!(vec.len(), 100);
+/// ```
+#[derive(Debug)]
+#[must_use = "nothing is appended unless it is pulled through the `LazyExtend`"]
+pub struct LazyExtend<'a, T, I> {
+    vec: &'a mut PushVec<T>,
+    iter: I,
+}
+
+impl<'a, T, I: Iterator<Item = T>> LazyExtend<'a, T, I> {
+    /// Returns the length of the `PushVec<T>` with the elements pulled so far.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns `true` if the `PushVec<T>` is empty, without pulling an
+    /// element.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Returns a reference to the element at the given index of the
+    /// `PushVec<T>`, pulling elements from the iterator until it exists.
+    /// Returns `None` if the iterator ends first.
+    #[inline]
+    pub fn get(&mut self, index: usize) -> Option<&T> {
+        while self.vec.len() <= index {
+            self.vec.push_mut(self.iter.next()?);
+        }
+        self.vec.0.get(index)
+    }
+
+    /// Pulls the next element from the iterator, and pushes it.
+    /// Returns a mutable reference to the pushed element, or `None` if the
+    /// iterator ended.
+    #[inline]
+    pub fn pull(&mut self) -> Option<&mut T> {
+        let item = self.iter.next()?;
+        Some(self.vec.push_mut(item))
+    }
+
+    /// Pulls all the remaining elements from the iterator.
+    /// Returns the length of the `PushVec<T>`.
+    #[inline]
+    pub fn finish(mut self) -> usize {
+        self.vec.extend(&mut self.iter);
+        self.vec.len()
+    }
+}
+
+/// A typed index of an element in a `PushVec<T>`.
+///
+/// Since elements are never removed, a handle stays valid for the lifetime of
+/// the `PushVec<T>` it was obtained from.
+pub struct Handle<T> {
+    index: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    #[inline]
+    const fn new(index: usize) -> Self {
+        Handle { index, _marker: PhantomData }
+    }
+
+    /// Returns the index of the element the handle refers to.
+    #[inline]
+    pub const fn index(self) -> usize {
+        self.index
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Handle").field(&self.index).finish()
+    }
+}
+
+/// A `PushVec<T>` that can no longer be pushed to, stored without spare
+/// capacity.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// let sealed = push_vec![1, 2, 3].seal();
+/// let x = unsafe { sealed.get(1) }.unwrap();
+/// assert_eq!(*x, 2);
+/// assert_eq!(unsafe { sealed.iter() }.sum::<i32>(), 6);
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct SealedVec<T>(Box<[T]>);
+
+impl<T> SealedVec<T> {
+    /// Returns the length of the `SealedVec<T>`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the `SealedVec<T>` is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Converts the `SealedVec<T>` into a boxed slice, without reallocating.
+    #[inline]
+    pub fn into_boxed_slice(self) -> Box<[T]> {
+        self.0
+    }
+
+    detached! {
+        /// Returns a reference to the elements, and doesn't borrow the
+        /// `SealedVec<T>`.
+        #[inline]
+        pub fn as_slice<'vec, 'a>(&'vec self) -> &'a [T]
+            where Self: Detach<'vec, 'a>,
+        {
+            Self::detach(&self.0, Token(()))
+        }
+    }
+
+    detached! {
+        /// Returns a mutable reference to the elements, and doesn't borrow the
+        /// `SealedVec<T>`.
+        ///
+        /// # Example
+        /// ```
+        /// use push_vec::prelude::*;
+        /// let mut sealed = push_vec![1, 2, 3].seal();
+        /// let slice = unsafe { sealed.as_mut_slice() };
+        /// slice[0] = 4;
+        /// assert_eq!(unsafe { sealed.as_slice() }, &[4, 2, 3]);
+        /// ```
+        #[inline]
+        pub fn as_mut_slice<'vec, 'a>(&'vec mut self) -> &'a mut [T]
+            where Self: Detach<'vec, 'a>,
+        {
+            Self::detach_mut(&mut self.0, Token(()))
+        }
+    }
+
+    detached! {
+        /// Returns a reference to the element at the given index, and doesn't
+        /// borrow the `SealedVec<T>`.
+        #[inline]
+        pub fn get<'vec, 'a>(&'vec self, index: usize) -> Option<&'a T>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_slice().get(index)
+        }
+    }
+
+    detached! {
+        /// Returns a mutable reference to the element at the given index, and
+        /// doesn't borrow the `SealedVec<T>`.
+        #[inline]
+        pub fn get_mut<'vec, 'a>(&'vec mut self, index: usize) -> Option<&'a mut T>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_mut_slice().get_mut(index)
+        }
+    }
+
+    detached! {
+        /// Returns an iterator over the elements of the `SealedVec<T>`.
+        /// Doesn't borrow the `SealedVec<T>`.
+        #[inline]
+        pub fn iter<'vec, 'a>(&'vec self) -> impl Iterator<Item = &'a T>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_slice().iter()
+        }
+    }
+
+    detached! {
+        /// Returns a mutable iterator over the elements of the `SealedVec<T>`.
+        /// Doesn't borrow the `SealedVec<T>`.
+        #[inline]
+        pub fn iter_mut<'vec, 'a>(&'vec mut self) -> impl Iterator<Item = &'a mut T>
+            where Self: Detach<'vec, 'a>,
+        {
+            self.as_mut_slice().iter_mut()
+        }
+    }
+}
+
+impl<T> From<SealedVec<T>> for PushVec<T> {
+    fn from(sealed: SealedVec<T>) -> Self {
+        PushVec::from_vec(sealed.0.into_vec())
+    }
+}
+
+/// An opaque token identifying an element of a `PushVec<T>` by its address.
+/// Created by `PushVec::push_token`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct StableToken(usize);
+
+/// A recorded length of a `PushVec<T>`, marking the start of a batch of
+/// pushes. Created by `PushVec::mark`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Mark(usize);
+
+impl Mark {
+    /// Returns the length the `PushVec<T>` had when the mark was recorded.
+    #[inline]
+    pub const fn position(self) -> usize {
+        self.0
+    }
+}
+
+/// A thread-safe handle to a `PushVec<T>` behind a lock. Cloning the handle
+/// shares the same `PushVec<T>`.
+///
+/// Unlike `PushVec<T>`, this doesn't hand out references to the elements,
+/// since they couldn't outlive the lock. Elements are read by copying them out
+/// instead.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// use std::thread;
+/// let shared = PushVec::new().into_shared();
+/// let threads: Vec<_> = (0..4)
+///     .map(|i| {
+///         let shared = shared.clone();
+///         thread::spawn(move || {
+///             shared.push(i);
+///         })
+///     })
+///     .collect();
+/// for thread in threads {
+///     thread.join().unwrap();
+/// }
+/// let mut vec = shared.try_into_push_vec().unwrap().into_vec();
+/// vec.sort();
+/// assert_eq!(vec, [0, 1, 2, 3]);
+/// ```
+#[derive(Debug)]
+pub struct SharedPushVec<T>(Arc<Mutex<PushVec<T>>>);
+
+impl<T> SharedPushVec<T> {
+    /// Locks the `PushVec<T>`. A panic while the lock was held can't leave a
+    /// `PushVec<T>` half-pushed, so poisoning is ignored.
+    fn lock(&self) -> MutexGuard<'_, PushVec<T>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the length of the shared `PushVec<T>`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if the shared `PushVec<T>` is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Pushes an element to the back of the shared `PushVec<T>`.
+    /// Returns a copy of the pushed element.
+    #[inline]
+    pub fn push(&self, item: T) -> T
+        where T: Clone,
+    {
+        self.lock().push_mut(item.clone());
+        item
+    }
+
+    /// Returns a copy of the element at the given index, or `None` if it's out
+    /// of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<T>
+        where T: Clone,
+    {
+        self.lock().0.get(index).cloned()
+    }
+
+    /// Returns a copy of all the elements.
+    #[inline]
+    pub fn snapshot(&self) -> Vec<T>
+        where T: Clone,
+    {
+        self.lock().snapshot()
+    }
+
+    /// Converts back into the `PushVec<T>`, if this is the only handle to it.
+    /// Otherwise, the handle is returned.
+    pub fn try_into_push_vec(self) -> Result<PushVec<T>, Self> {
+        match Arc::try_unwrap(self.0) {
+            Ok(mutex) => Ok(mutex.into_inner().unwrap_or_else(PoisonError::into_inner)),
+            Err(arc) => Err(SharedPushVec(arc)),
+        }
+    }
+}
+
+impl<T> Clone for SharedPushVec<T> {
+    fn clone(&self) -> Self {
+        SharedPushVec(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Default for SharedPushVec<T> {
+    fn default() -> Self {
+        SharedPushVec(Arc::new(Mutex::new(PushVec::new())))
+    }
+}
+
+/// A `PushVec<T>` with a fixed capacity of `CAP` elements, allocated up front.
+///
+/// It never reallocates, so references returned by `push` stay valid for as
+/// long as the `ArrayPushVec<T, CAP>` lives. Pushing to a full one fails
+/// instead.
+///
+/// # Example
+#[cfg_attr(feature = "safe-only", doc = "```ignore")]
+#[cfg_attr(not(feature = "safe-only"), doc = "```")]
+/// use push_vec::prelude::*;
+/// use push_vec::ArrayPushVec;
+/// let mut vec: ArrayPushVec<i32, 2> = ArrayPushVec::new();
+/// let x = unsafe { vec.push(1) }.unwrap();
+/// unsafe { vec.push(2) }.unwrap();
+/// assert_eq!(unsafe { vec.push(3) }, Err(3));
+/// *x = 4;
+/// assert_eq!(unsafe { vec.as_slice() }, &[4, 2]);
+/// ```
+#[derive(Eq, PartialEq, Hash, Debug)]
+pub struct ArrayPushVec<T, const CAP: usize>(PushVec<T>);
+
+impl<T, const CAP: usize> ArrayPushVec<T, CAP> {
+    /// Creates a new, empty `ArrayPushVec<T, CAP>`, allocating room for `CAP`
+    /// elements.
+    #[inline]
+    pub fn new() -> Self {
+        ArrayPushVec(PushVec::with_capacity(CAP))
+    }
+
+    /// Returns the length of the `ArrayPushVec<T, CAP>`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the `ArrayPushVec<T, CAP>` is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns `true` if the `ArrayPushVec<T, CAP>` holds `CAP` elements.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.0.len() == CAP
+    }
+
+    /// Returns the capacity, `CAP`.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        CAP
+    }
+
+    detached! {
+        /// Pushes an element to the back of the `ArrayPushVec<T, CAP>`.
+        /// Returns a mutable reference to the pushed element, or the element if
+        /// the `ArrayPushVec<T, CAP>` is full.
+        /// Doesn't borrow the `ArrayPushVec<T, CAP>`.
+        #[inline]
+        pub fn push<'vec, 'a>(&'vec mut self, item: T) -> Result<&'a mut T, T>
+            where PushVec<T>: Detach<'vec, 'a>,
+        {
+            if self.is_full() {
+                Err(item)
+            } else {
+                Ok(self.0.push(item))
+            }
+        }
+    }
+
+    detached! {
+        /// Returns a reference to the elements, and doesn't borrow the
+        /// `ArrayPushVec<T, CAP>`.
+        #[inline]
+        pub fn as_slice<'vec, 'a>(&'vec self) -> &'a [T]
+            where PushVec<T>: Detach<'vec, 'a>,
+        {
+            self.0.as_slice()
+        }
+    }
+
+    detached! {
+        /// Returns a mutable reference to the elements, and doesn't borrow the
+        /// `ArrayPushVec<T, CAP>`.
+        #[inline]
+        pub fn as_mut_slice<'vec, 'a>(&'vec mut self) -> &'a mut [T]
+            where PushVec<T>: Detach<'vec, 'a>,
+        {
+            self.0.as_mut_slice()
+        }
+    }
+
+    /// Converts the `ArrayPushVec<T, CAP>` into a `PushVec<T>`, which can grow
+    /// past `CAP`.
+    #[inline]
+    pub fn into_push_vec(self) -> PushVec<T> {
+        self.0
+    }
+}
+
+impl<T, const CAP: usize> Default for ArrayPushVec<T, CAP> {
+    fn default() -> Self {
+        ArrayPushVec::new()
+    }
+}
+
+impl<T: Clone, const CAP: usize> Clone for ArrayPushVec<T, CAP> {
+    fn clone(&self) -> Self {
+        // A cloned `Vec` only has room for its elements, but pushes must still
+        // never reallocate.
+        let mut vec = ArrayPushVec::new();
+        vec.0.0.extend_from_slice(&self.0.0);
+        vec
+    }
+}
+
+impl<T: Debug> Debug for PushVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PushVec").field(&self.0).finish()
+    }
+}
+
+/// A generational arena: a `PushVec<Option<T>>` whose elements can be removed
+/// and whose slots are then reused, with a generation per slot so that the
+/// handles of removed elements are rejected rather than aliasing new ones.
+///
+/// # Example
+/// ```
+/// use push_vec::GenArena;
+/// let mut arena = GenArena::new();
+/// let a = arena.insert("a");
+/// let b = arena.insert("b");
+/// assert_eq!(arena.remove(a), Some("a"));
+/// let c = arena.insert("c");
+/// assert_eq!(c.index(), a.index());
+/// assert_eq!(arena.get(a), None);
+/// assert_eq!(arena.get(b), Some(&"b"));
+/// assert_eq!(arena.get(c), Some(&"c"));
+/// assert_eq!(arena.len(), 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct GenArena<T> {
+    slots: PushVec<Option<T>>,
+    generations: Vec<u64>,
+    free: Vec<usize>,
+}
+
+impl<T> GenArena<T> {
+    /// Creates a new, empty `GenArena<T>`.
+    #[inline]
+    pub const fn new() -> Self {
+        GenArena { slots: PushVec::new(), generations: Vec::new(), free: Vec::new() }
+    }
+
+    /// Returns the number of elements in the `GenArena<T>`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    /// Returns `true` if the `GenArena<T>` has no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Inserts an element into the `GenArena<T>`, reusing the slot of a removed
+    /// element if there is one.
+    /// Returns a handle to the element.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::GenArena;
+    /// let mut arena = GenArena::new();
+    /// let x = arena.insert(1);
+    /// assert_eq!(arena.get(x), Some(&1));
+    /// ```
+    #[inline]
+    pub fn insert(&mut self, item: T) -> GenHandle<T> {
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.slots[index] = Some(item);
+                index
+            }
+            None => {
+                self.slots.push_mut(Some(item));
+                self.generations.push(0);
+                self.slots.len() - 1
+            }
+        };
+        GenHandle::new(index, self.generations[index])
+    }
+
+    /// Returns a reference to the element a handle refers to, or `None` if it
+    /// was removed.
+    #[inline]
+    pub fn get(&self, handle: GenHandle<T>) -> Option<&T> {
+        if self.generations.get(handle.index) != Some(&handle.generation) {
+            return None;
+        }
+        self.slots[handle.index].as_ref()
+    }
+
+    /// Returns a mutable reference to the element a handle refers to, or
+    /// `None` if it was removed.
+    #[inline]
+    pub fn get_mut(&mut self, handle: GenHandle<T>) -> Option<&mut T> {
+        if self.generations.get(handle.index) != Some(&handle.generation) {
+            return None;
+        }
+        self.slots[handle.index].as_mut()
+    }
+
+    /// Returns `true` if the element a handle refers to hasn't been removed.
+    #[inline]
+    pub fn contains(&self, handle: GenHandle<T>) -> bool {
+        self.get(handle).is_some()
+    }
+
+    /// Removes the element a handle refers to, returning it, or `None` if it
+    /// was already removed.
+    ///
+    /// The slot is left as a tombstone until the next insertion reuses it, and
+    /// every handle to it becomes stale.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::GenArena;
+    /// let mut arena = GenArena::new();
+    /// let x = arena.insert(1);
+    /// assert_eq!(arena.remove(x), Some(1));
+    /// assert_eq!(arena.remove(x), None);
+    /// assert!(!arena.contains(x));
+    /// ```
+    #[inline]
+    pub fn remove(&mut self, handle: GenHandle<T>) -> Option<T> {
+        if self.generations.get(handle.index) != Some(&handle.generation) {
+            return None;
+        }
+        let item = self.slots.tombstone(handle.index)?;
+        self.generations[handle.index] += 1;
+        self.free.push(handle.index);
+        Some(item)
+    }
+
+    /// Returns an iterator over the handles and the elements of the
+    /// `GenArena<T>`, in slot order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (GenHandle<T>, &T)> {
+        self.slots.0.iter()
+            .zip(&self.generations)
+            .enumerate()
+            .filter_map(|(index, (slot, &generation))| {
+                slot.as_ref().map(|item| (GenHandle::new(index, generation), item))
+            })
+    }
+}
+
+impl<T> Default for GenArena<T> {
+    fn default() -> Self {
+        GenArena::new()
+    }
+}
+
+/// A handle to an element of a `GenArena<T>`, which becomes stale once the
+/// element is removed.
+pub struct GenHandle<T> {
+    index: usize,
+    generation: u64,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> GenHandle<T> {
+    #[inline]
+    const fn new(index: usize, generation: u64) -> Self {
+        GenHandle { index, generation, _marker: PhantomData }
+    }
+
+    /// Returns the index of the slot the handle refers to.
+    #[inline]
+    pub const fn index(self) -> usize {
+        self.index
+    }
+
+    /// Returns the generation of the slot the handle was created for.
+    #[inline]
+    pub const fn generation(self) -> u64 {
+        self.generation
+    }
+}
+
+impl<T> Clone for GenHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for GenHandle<T> {}
+
+impl<T> PartialEq for GenHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for GenHandle<T> {}
+
+impl<T> Hash for GenHandle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for GenHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GenHandle")
+            .field("index", &self.index)
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
+
+/// Deduplicates values, assigning each distinct value a stable index. The
+/// values are stored in a `PushVec<T>`, so references to them can be held
+/// while more are interned.
+///
+/// Unlike `PushVec::push_unique`, lookups take constant time, at the cost of
+/// storing a clone of every value as a key.
+///
+/// # Example
+/// ```
+/// use push_vec::Interner;
+/// let mut interner = Interner::new();
+/// let a = interner.intern("a");
+/// let b = interner.intern("b");
+/// assert_eq!(interner.intern("a"), a);
+/// assert_ne!(a, b);
+/// assert_eq!(unsafe { interner.get(b) }, Some(&"b"));
+/// assert_eq!(interner.len(), 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Interner<T> {
+    values: PushVec<T>,
+    indices: HashMap<T, usize>,
+}
+
+impl<T: Eq + Hash + Clone> Interner<T> {
+    /// Creates a new, empty `Interner<T>`.
+    #[inline]
+    pub fn new() -> Self {
+        Interner { values: PushVec::new(), indices: HashMap::new() }
+    }
+
+    /// Returns the number of distinct values in the `Interner<T>`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the `Interner<T>` has no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the index of a value, pushing it first if it's new.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::Interner;
+    /// let mut interner = Interner::new();
+    /// let indices: Vec<_> = ["x", "y", "x", "x", "z", "y"]
+    ///     .iter()
+    ///     .map(|s| interner.intern(s.to_string()))
+    ///     .collect();
+    /// assert_eq!(indices, [0, 1, 0, 0, 2, 1]);
+    /// ```
+    #[inline]
+    pub fn intern(&mut self, value: T) -> usize {
+        if let Some(&index) = self.indices.get(&value) {
+            return index;
+        }
+        let index = self.values.len();
+        self.values.push_mut(value.clone());
+        self.indices.insert(value, index);
+        index
+    }
+
+    /// Returns the index of a value if it was interned, without pushing it.
+    #[inline]
+    pub fn lookup(&self, value: &T) -> Option<usize> {
+        self.indices.get(value).copied()
+    }
+
+    detached! {
+        /// Returns the value at the given index, or `None` if it's out of bounds.
+        /// Doesn't borrow the `Interner<T>`.
+        #[inline]
+        pub fn get<'vec, 'a>(&'vec self, index: usize) -> Option<&'a T>
+            where PushVec<T>: Detach<'vec, 'a>,
+        {
+            self.values.get(index)
+        }
+    }
+
+    detached! {
+        /// Returns the interned values, in the order they were first interned.
+        /// Doesn't borrow the `Interner<T>`.
+        #[inline]
+        pub fn as_slice<'vec, 'a>(&'vec self) -> &'a [T]
+            where PushVec<T>: Detach<'vec, 'a>,
+        {
+            self.values.as_slice()
+        }
+    }
+
+    /// Converts the `Interner<T>` into the `PushVec<T>` of its values.
+    #[inline]
+    pub fn into_push_vec(self) -> PushVec<T> {
+        self.values
+    }
+}
+
+impl<T: Eq + Hash + Clone> Default for Interner<T> {
+    fn default() -> Self {
+        Interner::new()
+    }
+}
+
+/// The `Debug` representation returned by `PushVec::debug_verbose`.
+struct VerboseDebug<'a, T>(&'a PushVec<T>);
+
+/// The elements of a `VerboseDebug`, each formatted along with its address.
+struct ElementsAt<'a, T>(&'a [T]);
+
+impl<T: Debug> Debug for VerboseDebug<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PushVec")
+            .field("len", &self.0.len())
+            .field("capacity", &self.0.capacity())
+            .field("elements", &ElementsAt(&self.0.0))
+            .finish()
+    }
+}
+
+impl<T: Debug> Debug for ElementsAt<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.0.iter().map(|x| (x as *const T, x)))
+            .finish()
+    }
+}
+
+/// The serialized form of a `PushVec<T>`, a newtype of its elements.
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+#[serde(rename = "PushVec")]
+struct PushVecRepr<V>(V);
+
+#[cfg(feature = "serde")]
+impl<T: Serialize> Serialize for PushVec<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PushVecRepr(&self.0).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for PushVec<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_newtype_struct("PushVec", PushVecVisitor(PhantomData))
+    }
+}
+
+/// Deserializes a `PushVec<T>`, reserving room for the elements up front when
+/// the format knows how many there are.
+#[cfg(feature = "serde")]
+struct PushVecVisitor<T>(PhantomData<T>);
+
+/// The most memory a size hint may reserve, as the hint of a malicious input
+/// can't be trusted. Beyond this, the `PushVec<T>` grows as it's filled.
+#[cfg(feature = "serde")]
+const MAX_PREALLOCATION: usize = 1024 * 1024;
+
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Visitor<'de> for PushVecVisitor<T> {
+    type Value = PushVec<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence")
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let max = MAX_PREALLOCATION / mem::size_of::<T>().max(1);
+        let mut vec = PushVec::with_capacity(seq.size_hint().unwrap_or(0).min(max));
+        while let Some(item) = seq.next_element()? {
+            vec.push(item);
+        }
+        Ok(vec)
+    }
+}
+
+/// A read-only view of a `PushVec<T>`, which exposes no way to push.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// use push_vec::PushVecView;
+/// fn sum(view: PushVecView<'_, i32>) -> i32 {
+///     view.iter().sum()
+/// }
+/// let vec = push_vec![1, 2, 3];
+/// let view = vec.view();
+/// assert_eq!(sum(view), 6);
+/// assert_eq!(view.get(1), Some(&2));
+/// assert!(!view.is_empty());
+/// ```
+#[derive(Debug)]
+pub struct PushVecView<'a, T>(&'a PushVec<T>);
+
+impl<'a, T> PushVecView<'a, T> {
+    /// Returns the length of the `PushVec<T>`.
+    #[inline]
+    pub fn len(self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the `PushVec<T>` is empty.
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns a reference to the elements.
+    #[inline]
+    pub fn as_slice(self) -> &'a [T] {
+        &self.0.0
+    }
+
+    /// Returns a reference to the element at the given index, or `None` if
+    /// it's out of bounds.
+    #[inline]
+    pub fn get(self, index: usize) -> Option<&'a T> {
+        self.as_slice().get(index)
+    }
+
+    /// Returns an iterator over the elements.
+    #[inline]
+    pub fn iter(self) -> impl Iterator<Item = &'a T> {
+        self.as_slice().iter()
+    }
+}
+
+impl<T> Clone for PushVecView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for PushVecView<'_, T> {}
+
+impl<T> AsRef<[T]> for PushVec<T> {
+    fn as_ref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T> AsMut<[T]> for PushVec<T> {
+    fn as_mut(&mut self) -> &mut [T] {
+        &mut self.0
+    }
+}
+
+impl<T> From<Vec<T>> for PushVec<T> {
+    fn from(vec: Vec<T>) -> Self {
+        PushVec::from_vec(vec)
+    }
+}
+
+impl<T> From<PushVec<T>> for Vec<T> {
+    fn from(push_vec: PushVec<T>) -> Self {
+        push_vec.into_vec()
+    }
+}
+
+/// Collects a range into a `PushVec<T>`.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// let vec: PushVec<i32> = PushVec::from(0..5);
+/// assert_eq!(vec, push_vec![0, 1, 2, 3, 4]);
+/// ```
+impl<T> From<Range<T>> for PushVec<T>
+    where Range<T>: Iterator<Item = T>
+{
+    fn from(range: Range<T>) -> Self {
+        range.collect()
+    }
+}
+
+/// Moves the elements of a `HashSet<T>` into a `PushVec<T>`, in an
+/// unspecified order.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// use std::collections::HashSet;
+/// let set: HashSet<i32> = [1, 2, 3].into_iter().collect();
+/// let mut vec = PushVec::from(set).into_vec();
+/// vec.sort();
+/// assert_eq!(vec, [1, 2, 3]);
+/// ```
+impl<T, S> From<HashSet<T, S>> for PushVec<T> {
+    fn from(set: HashSet<T, S>) -> Self {
+        set.into_iter().collect()
+    }
+}
+
+/// Moves the elements of a `BTreeSet<T>` into a `PushVec<T>`, in sorted
+/// order.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// use std::collections::BTreeSet;
+/// let set: BTreeSet<i32> = [3, 1, 2].into_iter().collect();
+/// assert_eq!(PushVec::from(set), push_vec![1, 2, 3]);
+/// ```
+impl<T> From<BTreeSet<T>> for PushVec<T> {
+    fn from(set: BTreeSet<T>) -> Self {
+        set.into_iter().collect()
+    }
+}
+
+/// Moves the elements of a `SmallVec` into a `PushVec<T>`.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// use smallvec::{smallvec, SmallVec};
+/// let small: SmallVec<[i32; 4]> = smallvec![1, 2, 3];
+/// assert_eq!(PushVec::from(small), push_vec![1, 2, 3]);
+/// ```
+#[cfg(feature = "smallvec")]
+impl<T, const N: usize> From<SmallVec<[T; N]>> for PushVec<T> {
+    fn from(small_vec: SmallVec<[T; N]>) -> Self {
+        PushVec::from_vec(small_vec.into_vec())
+    }
+}
+
+/// Moves the elements of a `PushVec<T>` into a `SmallVec`.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// use smallvec::SmallVec;
+/// let small: SmallVec<[i32; 4]> = push_vec![1, 2, 3].into();
+/// assert_eq!(small.as_slice(), &[1, 2, 3]);
+/// ```
+#[cfg(feature = "smallvec")]
+impl<T, const N: usize> From<PushVec<T>> for SmallVec<[T; N]> {
+    fn from(push_vec: PushVec<T>) -> Self {
+        SmallVec::from_vec(push_vec.into_vec())
+    }
+}
+
+impl<T> FromIterator<T> for PushVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        PushVec::from_vec(Vec::from_iter(iter))
+    }
+}
+
+/// Moves the elements out of the `PushVec<T>`, in push order.
+///
+/// Elements that aren't yielded are dropped front to back when the iterator
+/// is, as they are when the `PushVec<T>` itself is dropped, so destructors
+/// that depend on each other run in a predictable order.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// use std::cell::RefCell;
+/// struct Noisy<'a>(i32, &'a RefCell<Vec<i32>>);
+/// impl Drop for Noisy<'_> {
+///     fn drop(&mut self) {
+///         self.1.borrow_mut().push(self.0);
+///     }
+/// }
+/// let log = RefCell::new(Vec::new());
+/// drop(push_vec![Noisy(1, &log), Noisy(2, &log), Noisy(3, &log)]);
+/// assert_eq!(*log.borrow(), [1, 2, 3]);
+///
+/// log.borrow_mut().clear();
+/// let vec = push_vec![Noisy(1, &log), Noisy(2, &log), Noisy(3, &log)];
+/// let mut iter = vec.into_iter();
+/// drop(iter.next());
+/// drop(iter);
+/// assert_eq!(*log.borrow(), [1, 2, 3]);
+/// ```
+impl<T> IntoIterator for PushVec<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// Appends bytes to the `PushVec<u8>`, so that it can be written to with
+/// `write!`. Writing never fails.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// use std::io::Write;
+/// let mut vec = PushVec::new();
+/// write!(vec, "{} + {} = {}", 1, 2, 1 + 2).unwrap();
+/// assert_eq!(&vec[..], b"1 + 2 = 3");
+/// ```
+impl Write for PushVec<u8> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.grow(|vec| vec.extend_from_slice(buf));
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Appends the elements of an iterator to the `PushVec<T>`.
+///
+/// The elements are stored contiguously, so extending past the capacity
+/// reallocates, and the references previously returned by the `PushVec<T>`
+/// must not be used after that. Keeping them across an extension of any
+/// length would take storage that never moves its elements, which a
+/// `PushVec<T>` doesn't have. Reserve the capacity up front instead.
+///
+/// # Example
+#[cfg_attr(feature = "safe-only", doc = "```ignore")]
+#[cfg_attr(not(feature = "safe-only"), doc = "```")]
+/// use push_vec::prelude::*;
+/// let mut vec = PushVec::with_capacity(4);
+/// // SAFETY: `x` isn't used after the buffer grows.
+/// let x = unsafe { vec.push(1) };
+/// assert!(vec.can_push_without_realloc(3));
+/// vec.extend([2, 3, 4]);
+/// *x = 5;
+/// assert_eq!(vec, push_vec![5, 2, 3, 4]);
+/// ```
+impl<T> Extend<T> for PushVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.grow(|vec| vec.extend(iter));
+    }
+}
+
+/// Appends the elements of every `PushVec<T>` of an iterator, flattening it
+/// one level.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// let mut vec = push_vec![1];
+/// vec.extend(vec![push_vec![2, 3], push_vec![4]]);
+/// assert_eq!(vec, push_vec![1, 2, 3, 4]);
+/// ```
+impl<T> Extend<PushVec<T>> for PushVec<T> {
+    fn extend<I: IntoIterator<Item = PushVec<T>>>(&mut self, iter: I) {
+        for mut other in iter {
+            self.grow(|vec| vec.append(&mut other.0));
+        }
+    }
+}
+
+/// Appends the elements of another `PushVec<T>`.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// let mut vec = push_vec![1, 2];
+/// vec += push_vec![3, 4];
+/// assert_eq!(vec, push_vec![1, 2, 3, 4]);
+/// ```
+impl<T> AddAssign<PushVec<T>> for PushVec<T> {
+    fn add_assign(&mut self, mut other: PushVec<T>) {
+        self.grow(|vec| vec.append(&mut other.0));
+    }
+}
+
+/// Appends the elements of a `Vec<T>`.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// let mut vec = push_vec![1, 2];
+/// vec += vec![3, 4];
+/// assert_eq!(vec, push_vec![1, 2, 3, 4]);
+/// ```
+impl<T> AddAssign<Vec<T>> for PushVec<T> {
+    fn add_assign(&mut self, mut other: Vec<T>) {
+        self.grow(|vec| vec.append(&mut other));
+    }
+}
+
+/// Concatenates an iterator of `PushVec<T>`s into one `PushVec<T>`.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// let vecs = vec![push_vec![1, 2], push_vec![], push_vec![3]];
+/// let vec: PushVec<i32> = vecs.into_iter().sum();
+/// assert_eq!(vec, push_vec![1, 2, 3]);
+/// ```
+impl<T> Sum for PushVec<T> {
+    fn sum<I: Iterator<Item = PushVec<T>>>(iter: I) -> Self {
+        let mut vec = PushVec::new();
+        for mut other in iter {
+            vec.0.append(&mut other.0);
+        }
+        vec
+    }
+}
+
+impl<T: PartialEq> PartialEq<Vec<T>> for PushVec<T> {
+    fn eq(&self, other: &Vec<T>) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<T: PartialEq> PartialEq<[T]> for PushVec<T> {
+    fn eq(&self, other: &[T]) -> bool {
+        self.0 == other
+    }
+}
+
+/// Compares a `PushVec<T>` with a `Vec<T>` lexicographically.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// assert!(push_vec![1, 2] < vec![1, 3]);
+/// assert!(push_vec![1, 2] == vec![1, 2]);
+/// ```
+impl<T: PartialOrd> PartialOrd<Vec<T>> for PushVec<T> {
+    fn partial_cmp(&self, other: &Vec<T>) -> Option<Ordering> {
+        self.0.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+/// Compares a `PushVec<T>` with a slice lexicographically.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// let vec = push_vec![1, 2];
+/// assert!(vec < *[1, 3].as_slice());
+/// assert!(vec > *[1].as_slice());
+/// ```
+impl<T: PartialOrd> PartialOrd<[T]> for PushVec<T> {
+    fn partial_cmp(&self, other: &[T]) -> Option<Ordering> {
+        self.0.as_slice().partial_cmp(other)
+    }
+}
+
+/// Panics with the index and the length of the `PushVec<T>`.
+#[cold]
+#[inline(never)]
+fn index_out_of_bounds(index: &dyn Debug, len: usize) -> ! {
+    panic!("index {:?} out of bounds for PushVec of length {}", index, len)
+}
+
+mod index {
+    use std::ops::{Bound, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
+
+    /// An index whose start may lie past its end, like `3..2`.
+    ///
+    /// Such an index is left to the slice to reject, so it panics with the
+    /// slice's own message rather than claiming to be out of bounds.
+    pub trait Reversible {
+        fn is_reversed(&self) -> bool {
+            false
+        }
+    }
+
+    impl Reversible for usize {}
+    impl Reversible for RangeFrom<usize> {}
+    impl Reversible for RangeFull {}
+    impl Reversible for RangeTo<usize> {}
+    impl Reversible for RangeToInclusive<usize> {}
+
+    impl Reversible for Range<usize> {
+        fn is_reversed(&self) -> bool {
+            self.start > self.end
+        }
+    }
+
+    impl Reversible for RangeInclusive<usize> {
+        fn is_reversed(&self) -> bool {
+            self.end().checked_add(1).is_some_and(|end| *self.start() > end)
+        }
+    }
+
+    impl Reversible for (Bound<usize>, Bound<usize>) {
+        fn is_reversed(&self) -> bool {
+            let start = match self.0 {
+                Bound::Included(start) => Some(start),
+                Bound::Excluded(start) => start.checked_add(1),
+                Bound::Unbounded => Some(0),
+            };
+            let end = match self.1 {
+                Bound::Included(end) => end.checked_add(1),
+                Bound::Excluded(end) => Some(end),
+                Bound::Unbounded => None,
+            };
+            matches!((start, end), (Some(start), Some(end)) if start > end)
+        }
+    }
+}
+
+use index::Reversible;
+
+/// Indexes the `PushVec<T>` like a slice.
+///
+/// # Panics
+/// Panics if the index is out of bounds, or if it's a range that starts after
+/// it ends.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// use std::panic;
+/// let vec = push_vec![1, 2, 3, 4, 5];
+/// let err = panic::catch_unwind(|| vec[5]).unwrap_err();
+/// assert_eq!(
+///     err.downcast_ref::<String>().unwrap(),
+///     "index 5 out of bounds for PushVec of length 5",
+/// );
+/// #[allow(clippy::reversed_empty_ranges)]
+/// let err = panic::catch_unwind(|| &vec[3..2]).unwrap_err();
+/// assert_eq!(
+///     err.downcast_ref::<String>().unwrap(),
+///     "slice index starts at 3 but ends at 2",
+/// );
+/// ```
+impl<T, I> Index<I> for PushVec<T>
+    where I: SliceIndex<[T]> + Clone + Debug + Reversible
+{
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &I::Output {
+        if index.is_reversed() {
+            return &self.0[index];
+        }
+        match self.0.get(index.clone()) {
+            Some(output) => output,
+            None => index_out_of_bounds(&index, self.len()),
+        }
+    }
+}
+
+/// Mutably indexes the `PushVec<T>` like a slice.
+///
+/// # Panics
+/// Panics if the index is out of bounds, or if it's a range that starts after
+/// it ends.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// use std::panic::{self, AssertUnwindSafe};
+/// let mut vec = push_vec![1, 2, 3];
+/// let err = panic::catch_unwind(AssertUnwindSafe(|| vec[1..4][0] = 5)).unwrap_err();
+/// assert_eq!(
+///     err.downcast_ref::<String>().unwrap(),
+///     "index 1..4 out of bounds for PushVec of length 3",
+/// );
+/// ```
+impl<T, I> IndexMut<I> for PushVec<T>
+    where I: SliceIndex<[T]> + Clone + Debug + Reversible
+{
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        if index.is_reversed() {
+            return &mut self.0[index];
+        }
+        let len = self.len();
+        match self.0.get_mut(index.clone()) {
+            Some(output) => output,
+            None => index_out_of_bounds(&index, len),
+        }
+    }
+}
+
+/// An index counted from the back of a `PushVec<T>`: `FromEnd(0)` is the last
+/// element, `FromEnd(1)` the one before it, and so on.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// use push_vec::FromEnd;
+/// let mut vec = push_vec![1, 2, 3];
+/// assert_eq!(vec[FromEnd(0)], 3);
+/// vec[FromEnd(2)] = 4;
+/// assert_eq!(vec, push_vec![4, 2, 3]);
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct FromEnd(pub usize);
+
+impl FromEnd {
+    /// Returns the index from the front, or `None` if it's out of bounds.
+    #[inline]
+    fn to_front(self, len: usize) -> Option<usize> {
+        len.checked_sub(self.0)?.checked_sub(1)
+    }
+}
+
+/// Indexes the `PushVec<T>` from the back.
+///
+/// # Panics
+/// Panics if the index is out of bounds.
+///
+/// # Example
+/// ```
+/// use push_vec::prelude::*;
+/// use push_vec::FromEnd;
+/// use std::panic;
+/// let vec = push_vec![1, 2, 3];
+/// let err = panic::catch_unwind(|| vec[FromEnd(3)]).unwrap_err();
+/// assert_eq!(
+///     err.downcast_ref::<String>().unwrap(),
+///     "index FromEnd(3) out of bounds for PushVec of length 3",
+/// );
+/// ```
+impl<T> Index<FromEnd> for PushVec<T> {
+    type Output = T;
+
+    fn index(&self, index: FromEnd) -> &T {
+        match index.to_front(self.len()) {
+            Some(i) => &self.0[i],
+            None => index_out_of_bounds(&index, self.len()),
+        }
+    }
+}
+
+/// Mutably indexes the `PushVec<T>` from the back.
+///
+/// # Panics
+/// Panics if the index is out of bounds.
+impl<T> IndexMut<FromEnd> for PushVec<T> {
+    fn index_mut(&mut self, index: FromEnd) -> &mut T {
+        match index.to_front(self.len()) {
+            Some(i) => &mut self.0[i],
+            None => index_out_of_bounds(&index, self.len()),
+        }
+    }
+}
+
+/// An extension trait for collecting iterators into a `PushVec<T>`.
+pub trait IntoPushVec: Iterator {
+    /// Collects the iterator into a `PushVec<T>`.
+    /// This is the same as `collect::<PushVec<_>>()`.
+    ///
+    /// # Example
+    /// ```
+    /// use push_vec::prelude::*;
+    /// let vec = (1..4).map(|x| x * 2).collect_push_vec();
+    /// assert_eq!(vec, push_vec![2, 4, 6]);
+    /// ```
+    fn collect_push_vec(self) -> PushVec<Self::Item>;
+}
+
+impl<I: Iterator> IntoPushVec for I {
+    fn collect_push_vec(self) -> PushVec<Self::Item> {
+        self.collect()
+    }
+}
+
+
+/// A macro for creating a `PushVec` from a list of elements.
+#[macro_export]
+macro_rules! push_vec {
+    [$($x:expr),*] => {
+        {
+            PushVec::from_vec(vec![$($x),*])
+        }
+    };
+    [$x:expr; $n:expr] => {
+        {
+            PushVec::from_vec(vec![$x; $n])
+        }
+    };
+}
+
+/// A macro for creating a `PushVec` from a list of elements, with capacity
+/// reserved for at least `cap` more elements.
+///
+/// # Example
+#[cfg_attr(feature = "safe-only", doc = "```ignore")]
+#[cfg_attr(not(feature = "safe-only"), doc = "```")]
+/// use push_vec::prelude::*;
+/// let mut vec = push_vec_with_capacity![10; 1, 2, 3];
+/// assert_eq!(vec, push_vec![1, 2, 3]);
+/// assert!(vec.capacity() >= 13);
+/// // Pushing up to the reserved capacity doesn't reallocate.
+/// let x = unsafe { vec.push(4) };
+/// unsafe { vec.push(5) };
+/// *x = 6;
+/// assert_eq!(vec, push_vec![1, 2, 3, 6, 5]);
+/// ```
+#[macro_export]
+macro_rules! push_vec_with_capacity {
+    [$cap:expr; $($x:expr),*] => {
+        {
+            let mut vec = vec![$($x),*];
+            vec.reserve($cap);
+            $crate::PushVec::from_vec(vec)
         }
     };
 }
@@ -330,7 +4561,10 @@ macro_rules! push_vec {
 pub mod prelude {
     pub use super::{
         PushVec,
-        push_vec
+        PushVecBuilder,
+        IntoPushVec,
+        push_vec,
+        push_vec_with_capacity
     };
 }
 
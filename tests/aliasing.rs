@@ -0,0 +1,93 @@
+//! The references returned by successive pushes don't alias, and stay usable
+//! while the buffer doesn't grow. Growing moves the elements, so using a
+//! reference held across it is undefined behavior - this is why the methods
+//! that return detached references are `unsafe`. Run with
+//! `cargo +nightly miri test --test aliasing` to check the pointer provenance,
+//! not just the values, under both `-Zmiri-tree-borrows` and the default
+//! Stacked Borrows.
+#![cfg(not(feature = "safe-only"))]
+
+use push_vec::prelude::*;
+
+const N: usize = 256;
+
+#[test]
+fn successive_pushes_mutate_together() {
+    let mut vec = PushVec::with_capacity(2);
+    // SAFETY: The capacity was reserved, so neither push grows the buffer.
+    let (x, y) = unsafe { (vec.push(1), vec.push(2)) };
+    *x += 10;
+    *y += 20;
+    *x += *y;
+    assert_eq!(vec, push_vec![33, 22]);
+}
+
+#[test]
+fn many_pushes_within_capacity() {
+    let mut vec = PushVec::with_capacity(N);
+    // SAFETY: The capacity was reserved, so no push grows the buffer.
+    let refs: Vec<&mut usize> = (0..N).map(|i| unsafe { vec.push(i) }).collect();
+    for x in refs {
+        *x *= 2;
+    }
+    assert_eq!(vec.into_vec(), (0..N).map(|i| i * 2).collect::<Vec<_>>());
+}
+
+#[test]
+fn pushes_interleaved_with_reads() {
+    let mut vec = PushVec::builder(N).build();
+    // SAFETY: The builder reserved the capacity, so no push grows the buffer,
+    // and `first` isn't used after the whole buffer is read.
+    let first = unsafe { vec.push(0) };
+    for i in 1..N {
+        let last = unsafe { vec.push(i) };
+        *first += *last;
+        assert_eq!(vec.len(), i + 1);
+    }
+    let total = *first;
+    assert_eq!(total, N * (N - 1) / 2);
+    // Indexing reads through the whole buffer, so `first` isn't used after.
+    assert_eq!(vec[0], total);
+}
+
+#[test]
+fn push_and_context_splits_without_overlap() {
+    let mut vec = PushVec::with_capacity(N);
+    vec.extend([1]);
+    for _ in 1..N {
+        // Creating `before` reads every earlier element, so the references to
+        // them from earlier calls can't be written through any more. Each
+        // pushed element is written while its reference is the latest one.
+        //
+        // SAFETY: The capacity was reserved, so no push grows the buffer.
+        let (index, x, before) = unsafe { vec.push_and_context(0) };
+        *x = before[index - 1] + before[0];
+    }
+    assert_eq!(vec.into_vec(), (1..=N).collect::<Vec<_>>());
+}
+
+#[test]
+fn references_taken_again_after_growth() {
+    let mut vec = PushVec::new();
+    let mut refs: Vec<&mut usize> = Vec::new();
+    let mut growths = 0;
+    for i in 0..N {
+        if vec.can_push_without_realloc(1) {
+            // SAFETY: The push doesn't grow the buffer.
+            refs.push(unsafe { vec.push(i) });
+        } else {
+            // SAFETY: The push grows the buffer, so the references into the
+            // old one are given up before it, and taken again after it.
+            refs.clear();
+            unsafe { vec.push(i) };
+            refs = unsafe { vec.iter_mut() }.collect();
+            growths += 1;
+        }
+        for x in &mut refs {
+            **x += 1;
+        }
+    }
+    assert!(growths > 1);
+    // Each element was incremented once for every push since its own.
+    assert_eq!(vec.into_vec(), vec![N; N]);
+}
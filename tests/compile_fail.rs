@@ -0,0 +1,14 @@
+// Pins down the misuses of `PushVec` that the compiler rejects.
+//
+// References returned by the detached-lifetime methods (`push`, `get_mut`,
+// `as_slice`, ...) are not tied to any borrow of the `PushVec`, so moving or
+// dropping it while holding one is *not* rejected by the borrow checker.
+// Those methods are `unsafe` instead, and the `safe-only` feature turns the
+// misuse into a borrow error, see `tests/safe_only`.
+#![cfg(not(feature = "safe-only"))]
+
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile_fail/*.rs");
+}
@@ -0,0 +1,9 @@
+use push_vec::prelude::*;
+
+fn main() {
+    let mut vec = push_vec![1, 2, 3];
+    let slice: &mut [i32] = vec.as_mut();
+    let inner = vec.into_vec();
+    slice[0] = 4;
+    println!("{:?}", inner);
+}
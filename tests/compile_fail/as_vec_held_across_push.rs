@@ -0,0 +1,9 @@
+use push_vec::prelude::*;
+
+fn main() {
+    let mut vec = push_vec![1, 2, 3];
+    // The underlying `Vec` may reallocate, so it can't be held across a push.
+    let inner = vec.as_vec();
+    unsafe { vec.push(4) };
+    println!("{:?}", inner);
+}
@@ -0,0 +1,9 @@
+use push_vec::prelude::*;
+
+fn main() {
+    let mut vec = push_vec![1, 2, 3];
+    // Indexing borrows the vector, unlike `get_mut`.
+    let x = &mut vec[0];
+    unsafe { vec.push(4) };
+    *x = 5;
+}
@@ -0,0 +1,9 @@
+use push_vec::prelude::*;
+
+fn push_one(vec: &PushVec<i32>) {
+    unsafe { vec.push(1) };
+}
+
+fn main() {
+    push_one(&push_vec![]);
+}
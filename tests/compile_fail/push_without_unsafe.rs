@@ -0,0 +1,10 @@
+use push_vec::prelude::*;
+
+fn main() {
+    // The reference outlives the vector, so the push must be `unsafe`.
+    let x: &mut u32 = {
+        let mut vec = PushVec::new();
+        vec.push(1)
+    };
+    *x = 3;
+}
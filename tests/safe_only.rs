@@ -0,0 +1,23 @@
+// Checks that with the `safe-only` feature, references returned by `PushVec`
+// borrow it like the references returned by a `Vec`.
+#![cfg(feature = "safe-only")]
+
+use push_vec::prelude::*;
+
+#[test]
+fn references_are_borrowed() {
+    let mut vec = push_vec![1, 2, 3];
+    let x = vec.push(4);
+    *x = 5;
+    let y = vec.get_mut(0).unwrap();
+    *y = 6;
+    assert_eq!(vec.as_slice(), &[6, 2, 3, 5]);
+    let sum: i32 = vec.iter().sum();
+    assert_eq!(sum, 16);
+}
+
+#[test]
+fn detached_references_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/safe_only/*.rs");
+}
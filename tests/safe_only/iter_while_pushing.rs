@@ -0,0 +1,8 @@
+use push_vec::prelude::*;
+
+fn main() {
+    let mut vec = push_vec![1, 2, 3];
+    for x in vec.iter() {
+        vec.push(*x);
+    }
+}
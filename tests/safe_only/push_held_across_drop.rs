@@ -0,0 +1,9 @@
+use push_vec::prelude::*;
+
+fn main() {
+    let x: &mut u32 = {
+        let mut vec = PushVec::new();
+        vec.push(1)
+    };
+    *x = 3;
+}
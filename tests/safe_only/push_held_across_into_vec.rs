@@ -0,0 +1,9 @@
+use push_vec::prelude::*;
+
+fn main() {
+    let mut vec = PushVec::with_capacity(1);
+    let x = vec.push(1);
+    let inner = vec.into_vec();
+    *x = 2;
+    println!("{:?}", inner);
+}
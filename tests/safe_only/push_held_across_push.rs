@@ -0,0 +1,8 @@
+use push_vec::prelude::*;
+
+fn main() {
+    let mut vec = push_vec![];
+    let x = vec.push(1);
+    vec.push(2);
+    *x = 3;
+}
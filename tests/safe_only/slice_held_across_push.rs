@@ -0,0 +1,8 @@
+use push_vec::prelude::*;
+
+fn main() {
+    let mut vec = push_vec![1, 2, 3];
+    let slice = vec.as_slice();
+    vec.push(4);
+    println!("{:?}", slice);
+}
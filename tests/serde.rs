@@ -0,0 +1,38 @@
+#![cfg(feature = "serde")]
+
+use push_vec::prelude::*;
+
+#[test]
+fn round_trip() {
+    let vec = push_vec![1, 2, 3];
+    let json = serde_json::to_string(&vec).unwrap();
+    assert_eq!(json, "[1,2,3]");
+    assert_eq!(serde_json::from_str::<PushVec<i32>>(&json).unwrap(), vec);
+}
+
+#[test]
+fn borrowed_elements() {
+    let json = String::from(r#"["a", "bc"]"#);
+    let vec: PushVec<&str> = serde_json::from_str(&json).unwrap();
+    assert_eq!(vec, push_vec!["a", "bc"]);
+    // The elements point into the input, they weren't copied.
+    let range = json.as_bytes().as_ptr_range();
+    assert!(vec.iter().all(|s| range.contains(&s.as_ptr())));
+}
+
+#[test]
+fn reserves_from_size_hint() {
+    use serde::de::value::{Error, SeqDeserializer};
+    use serde::Deserialize;
+    let deserializer = SeqDeserializer::<_, Error>::new(0..1000);
+    let vec = PushVec::<i32>::deserialize(deserializer).unwrap();
+    assert_eq!(vec.len(), 1000);
+    assert_eq!(vec.capacity(), 1000);
+}
+
+#[test]
+fn large_array() {
+    let vec: PushVec<u32> = (0..100_000).collect();
+    let json = serde_json::to_string(&vec).unwrap();
+    assert_eq!(serde_json::from_str::<PushVec<u32>>(&json).unwrap(), vec);
+}